@@ -0,0 +1,394 @@
+use std::{
+    fmt::Write,
+    hint::black_box,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::Input,
+    registry::{Phase, Solution},
+    runner::Texts,
+};
+
+/// Where named baselines are stored, one JSON file each
+///
+/// Outside `target` so `cargo clean` doesn't throw them away.
+pub const BASELINE_DIR: &str = "benches/baselines";
+
+/// Two-sided 95% critical values of Student's t for 1 to 30 degrees of freedom
+const T_CRITICAL: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Mean and spread of repeated timings, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub runs: usize,
+    pub mean: f64,
+    /// Sample standard deviation
+    pub std_dev: f64,
+}
+
+impl Summary {
+    pub fn new(timings: &[Duration]) -> Self {
+        let runs = timings.len();
+        let nanos = timings.iter().map(|t| t.as_nanos() as f64);
+
+        let mean = nanos.clone().sum::<f64>() / runs as f64;
+        let variance = nanos.map(|t| (t - mean).powi(2)).sum::<f64>() / (runs - 1).max(1) as f64;
+
+        Self {
+            runs,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    fn variance_of_mean(&self) -> f64 {
+        self.std_dev.powi(2) / self.runs as f64
+    }
+}
+
+/// Timings of one phase of one solution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub solution: String,
+    pub phase: Phase,
+    pub summary: Summary,
+    /// [`fingerprint`] of the input it was measured on, empty in baselines saved before this was
+    /// recorded
+    #[serde(default)]
+    pub input: String,
+}
+
+/// FNV-1a hash of `text`, to tell whether two measurements were taken on the same input
+pub fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// Time `f` `runs` times, after one warm-up run
+fn time<T>(runs: usize, mut f: impl FnMut() -> Result<T>) -> Result<Summary> {
+    black_box(f()?);
+
+    let timings = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f()?);
+            Ok(start.elapsed())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Summary::new(&timings))
+}
+
+/// Time parsing and each part of `solution` `runs` times on the same input, read from `texts`
+pub fn measure(
+    solution: &'static Solution,
+    input: &Input,
+    texts: &mut Texts,
+    runs: usize,
+) -> Result<Vec<Measurement>> {
+    if runs < 2 {
+        bail!("Need at least 2 runs to measure spread, got {runs}");
+    }
+
+    let phases = solution.phases()?;
+    let text = texts
+        .get(solution, input, None)
+        .map_err(|outcome| eyre!("Reading the input {outcome}"))?;
+
+    let parse = time(runs, || phases.parse.text(black_box(&text), input))?;
+    let parsed = phases.parse.text(&text, input)?;
+
+    let [part_1, part_2] = phases
+        .parts
+        .map(|part| time(runs, || part(black_box(&parsed), input)));
+    let summaries = [parse, part_1?, part_2?];
+    let fingerprint = fingerprint(&text);
+
+    Ok(Phase::ALL
+        .into_iter()
+        .zip(summaries)
+        .map(|(phase, summary)| Measurement {
+            solution: solution.name.to_owned(),
+            phase,
+            summary,
+            input: fingerprint.clone(),
+        })
+        .collect())
+}
+
+pub fn baseline_path(name: &str) -> PathBuf {
+    PathBuf::from(BASELINE_DIR).join(format!("{name}.json"))
+}
+
+pub fn save_baseline(name: &str, measurements: &[Measurement]) -> Result<()> {
+    let path = baseline_path(name);
+    std::fs::create_dir_all(BASELINE_DIR)?;
+    std::fs::write(&path, serde_json::to_string_pretty(measurements)?)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn load_baseline(name: &str) -> Result<Vec<Measurement>> {
+    let path = baseline_path(name);
+    let json = std::fs::read_to_string(&path)
+        .wrap_err_with(|| format!("No baseline named {name:?} at {}", path.display()))?;
+
+    Ok(serde_json::from_str(&json)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    /// Within the threshold, or not statistically significant
+    Unchanged,
+    /// Not in the baseline
+    New,
+}
+
+/// A current measurement next to the matching one from a baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub current: Measurement,
+    pub baseline: Option<Summary>,
+}
+
+impl Comparison {
+    /// Relative change of the mean, positive when slower
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline?;
+        Some(self.current.summary.mean / baseline.mean - 1.0)
+    }
+
+    /// Whether the means differ at 95% confidence, by Welch's t-test
+    pub fn is_significant(&self) -> bool {
+        let Some(baseline) = self.baseline else {
+            return false;
+        };
+        let current = self.current.summary;
+
+        let difference = current.mean - baseline.mean;
+        let variance = current.variance_of_mean() + baseline.variance_of_mean();
+
+        // Perfectly consistent timings on both sides, any difference is real
+        if variance == 0.0 {
+            return difference != 0.0;
+        }
+
+        let t = difference / variance.sqrt();
+
+        // Welch–Satterthwaite, rounded down to stay conservative
+        let degrees_of_freedom = variance.powi(2)
+            / (current.variance_of_mean().powi(2) / (current.runs - 1) as f64
+                + baseline.variance_of_mean().powi(2) / (baseline.runs - 1) as f64);
+
+        let critical = match degrees_of_freedom.floor() as usize {
+            0 => T_CRITICAL[0],
+            df @ 1..=30 => T_CRITICAL[df - 1],
+            _ => 1.96,
+        };
+
+        t.abs() > critical
+    }
+
+    /// Classify the change, only counting significant changes beyond `threshold` (e.g. `0.05`)
+    pub fn verdict(&self, threshold: f64) -> Verdict {
+        let Some(change) = self.change() else {
+            return Verdict::New;
+        };
+
+        match change {
+            _ if !self.is_significant() => Verdict::Unchanged,
+            change if change > threshold => Verdict::Regressed,
+            change if change < -threshold => Verdict::Improved,
+            _ => Verdict::Unchanged,
+        }
+    }
+}
+
+/// Pair up current measurements with the baseline by solution and phase
+///
+/// Fails if any pair was measured on different inputs, since the timings then say more about
+/// the inputs than about the code.
+pub fn compare(current: &[Measurement], baseline: &[Measurement]) -> Result<Vec<Comparison>> {
+    current
+        .iter()
+        .map(|measurement| {
+            let baseline = baseline
+                .iter()
+                .find(|b| b.solution == measurement.solution && b.phase == measurement.phase);
+
+            if baseline.is_some_and(|b| b.input != measurement.input) {
+                bail!(
+                    "{} was measured on a different input than the baseline, save a new \
+                     baseline with this input to compare against",
+                    measurement.solution
+                );
+            }
+
+            Ok(Comparison {
+                current: measurement.clone(),
+                baseline: baseline.map(|b| b.summary),
+            })
+        })
+        .collect()
+}
+
+fn format_nanos(nanos: f64) -> String {
+    format!("{:.1?}", Duration::from_nanos(nanos as u64))
+}
+
+/// Table of mean timings with their spread, and each phase's share of its solution's total
+pub fn render_measurements(measurements: &[Measurement]) -> String {
+    let mut out = format!(
+        "{:<12} {:<6} {:>12} {:>12} {:>6}\n",
+        "solution", "phase", "mean", "std dev", "share"
+    );
+
+    for m in measurements {
+        let total: f64 = measurements
+            .iter()
+            .filter(|other| other.solution == m.solution)
+            .map(|other| other.summary.mean)
+            .sum();
+
+        writeln!(
+            out,
+            "{:<12} {:<6} {:>12} {:>12} {:>5.0}%",
+            m.solution,
+            m.phase.to_string(),
+            format_nanos(m.summary.mean),
+            format_nanos(m.summary.std_dev),
+            m.summary.mean / total * 100.0
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// Table of changes against the baseline, with a verdict per phase
+pub fn render_comparisons(comparisons: &[Comparison], threshold: f64) -> String {
+    let mut out = format!(
+        "{:<12} {:<6} {:>12} {:>12} {:>8}  verdict\n",
+        "solution", "phase", "baseline", "current", "change"
+    );
+
+    for comparison in comparisons {
+        let (baseline, change) = match (comparison.baseline, comparison.change()) {
+            (Some(baseline), Some(change)) => (
+                format_nanos(baseline.mean),
+                format!("{:+.1}%", change * 100.0),
+            ),
+            _ => ("-".to_owned(), "-".to_owned()),
+        };
+
+        let verdict = match comparison.verdict(threshold) {
+            Verdict::Regressed => "REGRESSED",
+            Verdict::Improved => "improved",
+            Verdict::Unchanged => "unchanged",
+            Verdict::New => "new",
+        };
+
+        writeln!(
+            out,
+            "{:<12} {:<6} {baseline:>12} {:>12} {change:>8}  {verdict}",
+            comparison.current.solution,
+            comparison.current.phase.to_string(),
+            format_nanos(comparison.current.summary.mean),
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(timings: &[u64]) -> Measurement {
+        let timings = timings
+            .iter()
+            .map(|&t| Duration::from_micros(t))
+            .collect::<Vec<_>>();
+
+        Measurement {
+            solution: "day01".to_owned(),
+            phase: Phase::Part1,
+            summary: Summary::new(&timings),
+            input: fingerprint("L68\n"),
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = measurement(&[2, 4, 4, 4, 5, 5, 7, 9]).summary;
+
+        assert_eq!(summary.runs, 8);
+        assert_eq!(summary.mean, 5000.0);
+        assert!((summary.std_dev - 2138.09).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let baseline = [measurement(&[100, 101, 99, 100, 102, 98, 100, 100])];
+
+        let slower = measurement(&[120, 121, 119, 120, 122, 118, 120, 120]);
+        let comparison = &compare(&[slower], &baseline).unwrap()[0];
+        assert!((comparison.change().unwrap() - 0.2).abs() < 1e-9);
+        assert_eq!(comparison.verdict(0.05), Verdict::Regressed);
+        assert_eq!(comparison.verdict(0.25), Verdict::Unchanged);
+
+        let faster = measurement(&[80, 81, 79, 80, 82, 78, 80, 80]);
+        assert_eq!(
+            compare(&[faster], &baseline).unwrap()[0].verdict(0.05),
+            Verdict::Improved
+        );
+
+        // A big change in the mean that's within the noise
+        let noisy = measurement(&[50, 200, 90, 180, 60, 190]);
+        let comparison = &compare(&[noisy], &baseline).unwrap()[0];
+        assert!(comparison.change().unwrap() > 0.05);
+        assert_eq!(comparison.verdict(0.05), Verdict::Unchanged);
+
+        let mut other_part = measurement(&[100, 100]);
+        other_part.phase = Phase::Part2;
+        assert_eq!(
+            compare(&[other_part], &baseline).unwrap()[0].verdict(0.05),
+            Verdict::New
+        );
+    }
+
+    #[test]
+    fn test_different_inputs() {
+        let baseline = [measurement(&[100, 101, 99])];
+        let mut current = measurement(&[100, 101, 99]);
+        current.input = fingerprint("R48\n");
+
+        assert!(compare(&[current], &baseline).is_err());
+        assert_ne!(fingerprint("L68\n"), fingerprint("L68\n\n"));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let measurements = vec![measurement(&[100, 101, 99])];
+        let json = serde_json::to_string(&measurements).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<Vec<Measurement>>(&json).unwrap(),
+            measurements
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::{collections::HashSet, fmt::Write, time::Duration};
+
+use color_eyre::eyre::{Result, eyre};
+
+use crate::http::Client;
+
+/// One response in the on-disk HTTP cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub url: String,
+    /// Stored size in bytes, the body plus a little bookkeeping
+    pub size: usize,
+    /// When it was stored, since the Unix epoch
+    pub time: Duration,
+}
+
+impl Entry {
+    /// Path of the URL, e.g. `/2025/day/1/input`
+    pub fn path(&self) -> &str {
+        self.url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|start| &rest[start..]))
+            .unwrap_or(&self.url)
+    }
+
+    /// The day a puzzle page, input or answer belongs to
+    pub fn day(&self) -> Option<u8> {
+        match self.path().trim_matches('/').split('/').collect::<Vec<_>>()[..] {
+            [_, "day", day, ..] => day.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<&cacache::Metadata> for Entry {
+    fn from(metadata: &cacache::Metadata) -> Self {
+        let url = metadata.key.strip_prefix("GET:").unwrap_or(&metadata.key);
+
+        Self {
+            url: url.to_owned(),
+            size: metadata.size,
+            time: Duration::from_millis(metadata.time as u64),
+        }
+    }
+}
+
+/// Every entry in the cache's index, in no particular order
+fn index(client: &Client) -> Result<Vec<cacache::Metadata>> {
+    // cacache fails to walk an index that was never created
+    if !client.cache_dir.join("index-v5").is_dir() {
+        return Ok(Vec::new());
+    }
+
+    cacache::list_sync(&client.cache_dir)
+        .map(|metadata| metadata.map_err(|e| eyre!("{e}")))
+        .collect()
+}
+
+/// Everything in the cache, sorted by URL
+pub fn list(client: &Client) -> Result<Vec<Entry>> {
+    let mut entries = index(client)?.iter().map(Entry::from).collect::<Vec<_>>();
+
+    entries.sort_by(|a, b| a.url.cmp(&b.url));
+    Ok(entries)
+}
+
+/// Which entries to purge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    All,
+    Day(u8),
+}
+
+/// Remove the selected entries and their content, returning what was removed
+///
+/// cacache stores identical bodies once for every entry that has them, so content is only
+/// deleted when no entry left in the cache refers to it.
+pub fn purge(client: &Client, selection: Selection) -> Result<Vec<Entry>> {
+    let (purged, kept): (Vec<_>, Vec<_>) =
+        index(client)?
+            .into_iter()
+            .partition(|metadata| match selection {
+                Selection::All => true,
+                Selection::Day(day) => Entry::from(metadata).day() == Some(day),
+            });
+
+    for metadata in &purged {
+        cacache::remove_sync(&client.cache_dir, &metadata.key)
+            .map_err(|e| eyre!("Failed to remove {}: {e}", metadata.key))?;
+    }
+
+    let referenced = kept
+        .iter()
+        .map(|metadata| &metadata.integrity)
+        .collect::<HashSet<_>>();
+    let orphaned = purged
+        .iter()
+        .map(|metadata| &metadata.integrity)
+        .filter(|integrity| !referenced.contains(integrity))
+        .collect::<HashSet<_>>();
+
+    for integrity in orphaned {
+        cacache::remove_hash_sync(&client.cache_dir, integrity)
+            .map_err(|e| eyre!("Failed to remove content {integrity}: {e}"))?;
+    }
+
+    let mut entries = purged.iter().map(Entry::from).collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.url.cmp(&b.url));
+    Ok(entries)
+}
+
+/// Format a time since the Unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC
+pub fn format_utc(time: Duration) -> String {
+    let seconds = time.as_secs();
+    let days = (seconds / 86400) as i64;
+
+    // Days to a civil date, from Howard Hinnant's `civil_from_days`
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        seconds / 3600 % 24,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Table of cached entries with their size and when they were stored
+pub fn render(entries: &[Entry]) -> String {
+    let mut out = format!("{:<19} {:>9}  url\n", "stored (UTC)", "bytes");
+
+    for entry in entries {
+        writeln!(
+            out,
+            "{} {:>9}  {}",
+            format_utc(entry.time),
+            entry.size,
+            entry.url
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str) -> Entry {
+        Entry {
+            url: url.to_owned(),
+            size: 0,
+            time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_day() {
+        assert_eq!(
+            entry("https://adventofcode.com/2025/day/1/input").day(),
+            Some(1)
+        );
+        assert_eq!(
+            entry("https://adventofcode.com/2025/day/12").day(),
+            Some(12)
+        );
+        assert_eq!(
+            entry("http://127.0.0.1:80/2025/day/3/answer").day(),
+            Some(3)
+        );
+        assert_eq!(
+            entry("https://adventofcode.com/2025/leaderboard/self").day(),
+            None
+        );
+        assert_eq!(entry("https://adventofcode.com/2025/day/x").day(), None);
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(Duration::ZERO), "1970-01-01 00:00:00");
+        assert_eq!(
+            format_utc(Duration::from_secs(1764565200)),
+            "2025-12-01 05:00:00"
+        );
+        assert_eq!(
+            format_utc(Duration::from_secs(951782400 + 86399)),
+            "2000-02-29 23:59:59"
+        );
+    }
+
+    #[test]
+    fn test_purge() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("aoc-cache-purge-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        let client = Client {
+            base_url: "https://adventofcode.com".to_owned(),
+            session: None,
+            user_agent: String::new(),
+            cache_dir,
+            min_interval: Duration::ZERO,
+        };
+
+        for path in ["/2025/day/1", "/2025/day/1/input", "/2025/day/2/input"] {
+            cacache::write_sync(&client.cache_dir, client.cache_key(path), path).unwrap();
+        }
+        // Same body as day 1's input, so the same content
+        let shared = client.cache_key("/2025/day/3/input");
+        cacache::write_sync(&client.cache_dir, &shared, "/2025/day/1/input").unwrap();
+
+        let removed = purge(&client, Selection::Day(1)).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(!client.is_cached("/2025/day/1/input"));
+
+        let remaining = list(&client).unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[0].path(), "/2025/day/2/input");
+        assert_eq!(
+            cacache::read_sync(&client.cache_dir, &shared).unwrap(),
+            b"/2025/day/1/input"
+        );
+
+        purge(&client, Selection::All).unwrap();
+        assert!(list(&client).unwrap().is_empty());
+    }
+}
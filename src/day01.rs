@@ -1,73 +1,318 @@
-use color_eyre::eyre::{Result, bail};
+use std::fmt::Write;
 
-use crate::fetch_input;
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
 
-/// Parse line into offset
-fn parse_line(line: &str) -> Result<i32> {
-    let (dir, offset) = line.split_at(1);
-    let offset = offset.parse::<i32>()?;
+use crate::input::Input;
 
-    let offset = match dir {
-        "L" => -offset,
-        "R" => offset,
-        _ => bail!("Invalid direction"),
+/// Parse line into offset, negative for left rotations
+pub fn parse_line(line: &str) -> Result<i32> {
+    let mut chars = line.chars();
+    let direction = match chars.next() {
+        Some('L') => -1,
+        Some('R') => 1,
+        Some(other) => bail!("Unknown direction {other:?}, expected L or R"),
+        None => bail!("Empty line"),
     };
 
-    Ok(offset)
+    // `parse` would also take a sign, which has no business here
+    let magnitude = chars.as_str();
+    if magnitude.is_empty() || !magnitude.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("Bad magnitude {magnitude:?}, expected digits");
+    }
+    let magnitude = magnitude
+        .parse::<i32>()
+        .map_err(|_| eyre!("Magnitude {magnitude} overflows"))?;
+
+    Ok(direction * magnitude)
+}
+
+/// [`parse_line`] for line `number`, counting from 1, saying where it went wrong
+pub fn parse_numbered(number: usize, line: &str) -> Result<i32> {
+    parse_line(line).wrap_err_with(|| format!("Line {number}: {line:?}"))
+}
+
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<i32>;
+
+/// Parse one rotation per line
+pub fn parse(input: &str) -> Result<Parsed> {
+    parse_with(input, false)
+}
+
+/// Parse one rotation per line, leaving out blank lines with `skip_blank`
+pub fn parse_with(input: &str, skip_blank: bool) -> Result<Parsed> {
+    (1..)
+        .zip(input.lines())
+        .filter(|(_, line)| !(skip_blank && line.trim().is_empty()))
+        .map(|(number, line)| parse_numbered(number, line))
+        .collect()
+}
+
+/// Whether to skip blank lines, set with the `skip_blank` parameter
+pub fn skip_blank(input: &Input) -> Result<bool> {
+    Ok(input.param("skip_blank")?.unwrap_or(false))
+}
+
+/// What one rotation did to the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub offset: i32,
+    pub start: i32,
+    pub end: i32,
+    /// Clicks that pointed at the target, including the last one
+    pub passes: u32,
+    /// Whether it stopped at the target
+    pub landed: bool,
+}
+
+/// How often each position of a dial was stopped at and pointed at, from [`Dial::histogram`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// Rotations that stopped there, by position
+    pub landed: Vec<u64>,
+    /// Clicks that pointed there, by position
+    pub passed: Vec<u64>,
 }
 
-fn part_1(input: &str) -> Result<i32> {
-    let mut dial = 50;
-    let mut count = 0;
+impl Histogram {
+    fn index(&self, position: i32) -> Result<usize> {
+        let size = self.landed.len();
+        usize::try_from(position)
+            .ok()
+            .filter(|&position| position < size)
+            .ok_or_else(|| eyre!("Position {position} isn't on a dial of size {size}"))
+    }
+
+    /// How many rotations stopped at `position`, like part 1 with it as the target
+    pub fn landed(&self, position: i32) -> Result<u64> {
+        Ok(self.landed[self.index(position)?])
+    }
 
-    for line in input.lines() {
-        let offset = parse_line(line)?;
+    /// How many clicks pointed at `position`, like part 2 with it as the target
+    pub fn passed(&self, position: i32) -> Result<u64> {
+        Ok(self.passed[self.index(position)?])
+    }
 
-        dial = (dial + offset).rem_euclid(100);
+    /// One row per position, with a header
+    pub fn to_csv(&self) -> String {
+        let mut out = "position,landed,passed\n".to_owned();
 
-        if dial == 0 {
-            count += 1;
+        for (position, (landed, passed)) in self.landed.iter().zip(&self.passed).enumerate() {
+            writeln!(out, "{position},{landed},{passed}").unwrap();
         }
+
+        out
     }
+}
 
-    Ok(count)
+/// A dial numbered `0..size`, pointing at `position`, on the lookout for `target`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    pub size: i32,
+    pub position: i32,
+    pub target: i32,
 }
 
-fn part_2(input: &str) -> Result<u32> {
-    let mut dial = 50;
-    let mut count = 0;
+impl Dial {
+    /// The puzzle's dial: 100 positions, starting at 50, counting zeroes
+    pub const PUZZLE: Self = Self {
+        size: 100,
+        position: 50,
+        target: 0,
+    };
+
+    pub fn new(size: i32, start: i32, target: i32) -> Result<Self> {
+        if size <= 0 {
+            bail!("Dial size should be positive, got {size}");
+        }
+        for (what, n) in [("start", start), ("target", target)] {
+            if !(0..size).contains(&n) {
+                bail!("Dial {what} should be in 0..{size}, got {n}");
+            }
+        }
+
+        Ok(Self {
+            size,
+            position: start,
+            target,
+        })
+    }
+
+    /// Rotate by `offset` clicks, returning whether it stopped at the target
+    pub fn turn(&mut self, offset: i32) -> bool {
+        // In i64 since any `i32` offset is allowed, which can overflow added to the position
+        let position = i64::from(self.position) + i64::from(offset);
+        self.position = position.rem_euclid(self.size.into()) as i32;
+        self.position == self.target
+    }
+
+    /// Rotate by `offset` clicks, returning how many of them landed on the target
+    pub fn turn_counting_passes(&mut self, offset: i32) -> u32 {
+        let (size, position, target) = (
+            i64::from(self.size),
+            i64::from(self.position),
+            i64::from(self.target),
+        );
+        let offset = i64::from(offset);
+
+        // Every full turn passes the target once, which leaves less than one turn to go
+        let (turns, rest) = (offset.abs() / size, offset.abs() % size);
+        let (rest, ahead) = if offset < 0 {
+            (-rest, position - target)
+        } else {
+            (rest, target - position)
+        };
+
+        // Clicks until the target comes round, a full turn when already on it
+        let until_target = if ahead <= 0 { ahead + size } else { ahead };
+
+        self.position = (position + rest).rem_euclid(size) as i32;
+
+        turns as u32 + u32::from(rest.abs() >= until_target)
+    }
+
+    /// What each of `rotations` does, in order
+    pub fn trace(mut self, rotations: &[i32]) -> impl Iterator<Item = Step> + '_ {
+        rotations.iter().map(move |&offset| {
+            let start = self.position;
+            let passes = self.turn_counting_passes(offset);
 
-    for line in input.lines() {
-        let offset = parse_line(line)?;
+            Step {
+                offset,
+                start,
+                end: self.position,
+                passes,
+                landed: self.position == self.target,
+            }
+        })
+    }
+
+    /// Landings and passes for every position at once, whatever the target
+    pub fn histogram(self, rotations: &[i32]) -> Histogram {
+        let size = self.size as usize;
+        let mut landed = vec![0; size];
+        let mut full_turns = 0;
+        // Differences between the passes of neighbouring positions, for the partial turns
+        let mut extra = vec![0i64; size + 1];
+        let mut position = self.position as usize;
+
+        for &offset in rotations {
+            let clicks = offset.unsigned_abs() as usize;
+            full_turns += (clicks / size) as u64;
+            let rest = clicks % size;
 
-        let new_dial_unwrapped = dial + offset;
+            // The positions after the start, up to and including where it stops
+            let first = if offset < 0 {
+                (position + size - rest) % size
+            } else {
+                (position + 1) % size
+            };
+            if rest > 0 {
+                let end = first + rest;
+                extra[first] += 1;
+                if end <= size {
+                    extra[end] -= 1;
+                } else {
+                    extra[size] -= 1;
+                    extra[0] += 1;
+                    extra[end - size] -= 1;
+                }
+            }
 
-        if offset > 0 {
-            count += (dial + offset) as u32 / 100;
-        } else if offset < 0 {
-            count += i32::abs_diff((dial).div_ceil(100), (new_dial_unwrapped).div_ceil(100));
+            position = if offset < 0 {
+                first
+            } else {
+                (position + rest) % size
+            };
+            landed[position] += 1;
         }
 
-        dial = new_dial_unwrapped.rem_euclid(100);
+        let passed = extra[..size]
+            .iter()
+            .scan(0, |running, difference| {
+                *running += difference;
+                Some(full_turns + *running as u64)
+            })
+            .collect();
+
+        Histogram { landed, passed }
+    }
+
+    /// How many `rotations` stop at the target
+    pub fn count_landings(mut self, rotations: &[i32]) -> u64 {
+        rotations
+            .iter()
+            .filter(|&&offset| self.turn(offset))
+            .count() as u64
     }
 
-    Ok(count)
+    /// How many clicks over all `rotations` land on the target
+    pub fn count_passes(mut self, rotations: &[i32]) -> u64 {
+        rotations
+            .iter()
+            .map(|&offset| u64::from(self.turn_counting_passes(offset)))
+            .sum()
+    }
+}
+
+/// The dial to use, overridable with the `size`, `start` and `target` parameters
+pub fn dial(input: &Input) -> Result<Dial> {
+    let puzzle = Dial::PUZZLE;
+
+    Dial::new(
+        input.param("size")?.unwrap_or(puzzle.size),
+        input.param("start")?.unwrap_or(puzzle.position),
+        input.param("target")?.unwrap_or(puzzle.target),
+    )
 }
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(1)?;
+/// Table of steps with running totals, rotations written back as in the input
+pub fn render_trace(steps: impl IntoIterator<Item = Step>) -> String {
+    let mut out = format!(
+        "{:>5} {:>8} {:>6} {:>6} {:>7} {:>7}\n",
+        "#", "rotation", "start", "end", "passes", "landed"
+    );
+    let (mut passes, mut landings) = (0, 0);
+
+    for (i, step) in steps.into_iter().enumerate() {
+        let direction = if step.offset < 0 { 'L' } else { 'R' };
+        passes += u64::from(step.passes);
+        landings += u64::from(step.landed);
+
+        writeln!(
+            out,
+            "{:>5} {:>8} {:>6} {:>6} {:>7} {:>7}",
+            i + 1,
+            format!("{direction}{}", step.offset.abs()),
+            step.start,
+            step.end,
+            step.passes,
+            if step.landed { "yes" } else { "no" }
+        )
+        .unwrap();
+    }
 
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&input)?);
+    writeln!(
+        out,
+        "{:>5} {:>8} {:>6} {:>6} {passes:>7} {landings:>7}",
+        "total", "", "", ""
+    )
+    .unwrap();
 
-    Ok(())
+    out
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Count how many rotations leave the dial pointing at 0
+pub fn part_1(rotations: &[i32]) -> Result<u64> {
+    Ok(Dial::PUZZLE.count_landings(rotations))
+}
+
+/// Count how many times the dial points at 0, including while rotating
+pub fn part_2(rotations: &[i32]) -> Result<u64> {
+    Ok(Dial::PUZZLE.count_passes(rotations))
+}
 
-    const SAMPLE_INPUT: &str = "L68
+const SAMPLE_INPUT: &str = "L68
 L30
 R48
 L5
@@ -79,19 +324,201 @@ R14
 L82
 ";
 
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::{day01_generator, fetch_input, oracle};
+
+    /// Turn the dial one click at a time, counting targets after each rotation and each click
+    fn brute_force(dial: Dial, rotations: &[i32]) -> (u64, u64) {
+        let mut position = dial.position;
+        let (mut landed, mut passed) = (0, 0);
+
+        for &offset in rotations {
+            for _ in 0..offset.abs() {
+                position = (position + offset.signum()).rem_euclid(dial.size);
+                if position == dial.target {
+                    passed += 1;
+                }
+            }
+
+            if position == dial.target {
+                landed += 1;
+            }
+        }
+
+        (landed, passed)
+    }
+
     #[test]
     fn test_part_1() {
         let real_input = fetch_input(1).unwrap();
 
-        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 3);
-        assert_eq!(part_1(&real_input).unwrap(), 1147);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 3);
+        assert_eq!(part_1(&parse(&real_input).unwrap()).unwrap(), 1147);
     }
 
     #[test]
     fn test_part_2() {
         let real_input = fetch_input(1).unwrap();
 
-        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 6);
-        assert_eq!(part_2(&real_input).unwrap(), 6789);
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 6);
+        assert_eq!(part_2(&parse(&real_input).unwrap()).unwrap(), 6789);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(
+            |rng| day01_generator::random_input(rng, 50),
+            |input| {
+                let rotations = parse(input).unwrap();
+                let (landed, passed) = brute_force(Dial::PUZZLE, &rotations);
+
+                assert_eq!(part_1(&rotations).unwrap(), landed);
+                assert_eq!(part_2(&rotations).unwrap(), passed);
+            },
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let error = |input: &str| format!("{:#}", parse(input).unwrap_err());
+
+        assert_eq!(error("L1\n\nR2\n"), "Line 2: \"\": Empty line");
+        assert_eq!(
+            error("L1\nR2\néR3\n"),
+            "Line 3: \"éR3\": Unknown direction 'é', expected L or R"
+        );
+        assert_eq!(
+            error("R-5\n"),
+            "Line 1: \"R-5\": Bad magnitude \"-5\", expected digits"
+        );
+        assert_eq!(
+            error("L\n"),
+            "Line 1: \"L\": Bad magnitude \"\", expected digits"
+        );
+        assert_eq!(
+            error("R99999999999\n"),
+            "Line 1: \"R99999999999\": Magnitude 99999999999 overflows"
+        );
+
+        assert_eq!(parse_with("L1\n\n  \nR2\n", true).unwrap(), [-1, 2]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let rotations = parse(SAMPLE_INPUT).unwrap();
+        let steps = Dial::PUZZLE.trace(&rotations).collect::<Vec<_>>();
+
+        assert_eq!(
+            steps[..2],
+            [
+                Step {
+                    offset: -68,
+                    start: 50,
+                    end: 82,
+                    passes: 1,
+                    landed: false
+                },
+                Step {
+                    offset: -30,
+                    start: 82,
+                    end: 52,
+                    passes: 0,
+                    landed: false
+                },
+            ]
+        );
+        assert_eq!(steps.iter().map(|step| step.passes).sum::<u32>(), 6);
+        assert_eq!(steps.iter().filter(|step| step.landed).count(), 3);
+
+        let table = render_trace(steps);
+        assert!(table.contains("\n    3      R48     52      0       1     yes\n"));
+        assert!(table.contains("\n    4       L5      0     95       0      no\n"));
+        assert!(table.ends_with("\ntotal                              6       3\n"));
+    }
+
+    #[test]
+    fn test_other_dials() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(1..=120);
+                let dial =
+                    Dial::new(size, rng.random_range(0..size), rng.random_range(0..size)).unwrap();
+
+                (dial, day01_generator::random_input(rng, 50))
+            },
+            |(dial, input)| {
+                let rotations = parse(input).unwrap();
+                let (landed, passed) = brute_force(*dial, &rotations);
+
+                assert_eq!(dial.count_landings(&rotations), landed);
+                assert_eq!(dial.count_passes(&rotations), passed);
+            },
+        );
+
+        assert!(Dial::new(60, 17, 60).is_err());
+        assert!(Dial::new(0, 0, 0).is_err());
+    }
+
+    /// Rotations and dials as big as an `i32` allows, which overflow if added up in one
+    #[test]
+    fn test_huge_rotations() {
+        let rotations = parse("R2147483647\nL2147483647\nR2147483647\n").unwrap();
+        let mut dial = Dial::PUZZLE;
+        assert!(!dial.turn(i32::MAX));
+        assert_eq!(dial.position, 97);
+        assert_eq!(Dial::PUZZLE.count_landings(&rotations), 0);
+        assert_eq!(Dial::PUZZLE.count_passes(&rotations), 3 * 21474836);
+
+        let mut passing = Dial::new(i32::MAX, i32::MAX - 1, 0).unwrap();
+        let mut landing = passing;
+        assert_eq!(passing.turn_counting_passes(i32::MAX - 1), 1);
+        assert_eq!(passing.position, i32::MAX - 2);
+        assert!(!landing.turn(i32::MAX - 1));
+        assert_eq!(landing.position, i32::MAX - 2);
+        assert!(landing.turn(2));
+    }
+
+    /// Every position counts the same as a dial with it as the target
+    #[test]
+    fn test_histogram() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(1..=120);
+                (
+                    Dial::new(size, rng.random_range(0..size), 0).unwrap(),
+                    day01_generator::random_input(rng, 50),
+                )
+            },
+            |(dial, input)| {
+                let rotations = parse(input).unwrap();
+                let histogram = dial.histogram(&rotations);
+
+                for target in 0..dial.size {
+                    let dial = Dial { target, ..*dial };
+                    assert_eq!(
+                        histogram.landed(target).unwrap(),
+                        dial.count_landings(&rotations)
+                    );
+                    assert_eq!(
+                        histogram.passed(target).unwrap(),
+                        dial.count_passes(&rotations)
+                    );
+                }
+                assert!(histogram.landed(dial.size).is_err());
+                assert!(histogram.passed(-1).is_err());
+            },
+        );
+
+        let rotations = parse(SAMPLE_INPUT).unwrap();
+        let csv = Dial::PUZZLE.histogram(&rotations).to_csv();
+        assert_eq!(csv.lines().count(), 101);
+        assert!(csv.starts_with("position,landed,passed\n0,3,6\n"));
     }
 }
@@ -0,0 +1,295 @@
+//! Day 1's dial drawn in the terminal, turning through the rotations
+
+use std::{f64::consts::TAU, fmt::Write as _, io::Write, iter, thread, time::Duration};
+
+use color_eyre::eyre::{Result, bail};
+
+use crate::day01::Dial;
+
+/// Rows from the centre of the ring to its top, twice as many columns across since cells are tall
+const RADIUS: f64 = 12.0;
+
+/// More frames than this a second only make the terminal flicker
+const MAX_FPS: f64 = 30.0;
+
+/// How many frames' worth of time a landing stays on screen
+const LANDING_HOLD_FRAMES: f64 = 15.0;
+
+/// The dial part way through the rotations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    /// Rotations started so far, 0 before the first
+    pub rotation: usize,
+    /// The rotation underway
+    pub offset: i32,
+    pub position: i32,
+    /// Clicks that pointed at the target so far
+    pub passes: u64,
+    /// Rotations that stopped at the target so far
+    pub landings: u64,
+    /// Whether the rotation underway just stopped at the target
+    pub landed: bool,
+}
+
+/// The dial before any rotations, then after every `clicks_per_frame` clicks of each of them
+pub fn frames(
+    dial: Dial,
+    rotations: &[i32],
+    clicks_per_frame: u32,
+) -> impl Iterator<Item = Frame> + '_ {
+    let mut dial = dial;
+    let mut frame = Frame {
+        rotation: 0,
+        offset: 0,
+        position: dial.position,
+        passes: 0,
+        landings: 0,
+        landed: false,
+    };
+    let mut started = false;
+    // Clicks still to go in the rotation underway, negative to the left
+    let mut left = 0;
+
+    iter::from_fn(move || {
+        if !started {
+            started = true;
+            return Some(frame);
+        }
+
+        if left == 0 {
+            frame.offset = *rotations.get(frame.rotation)?;
+            frame.rotation += 1;
+            left = frame.offset;
+        }
+
+        let step = left.signum() * left.unsigned_abs().min(clicks_per_frame.max(1)) as i32;
+        left -= step;
+        frame.passes += u64::from(dial.turn_counting_passes(step));
+        frame.position = dial.position;
+        frame.landed = left == 0 && dial.position == dial.target;
+        frame.landings += u64::from(frame.landed);
+
+        Some(frame)
+    })
+}
+
+/// `frame` on `dial` as a ring of positions with 0 at the top, and the counts underneath
+///
+/// The pointer is `@` and the target `o`. With `ansi` the pointer is coloured, green on a
+/// landing, and so is the line saying so.
+pub fn draw(dial: Dial, frame: &Frame, total: usize, ansi: bool) -> String {
+    let (rows, columns) = (2 * RADIUS as usize + 1, 4 * RADIUS as usize + 1);
+    let mut grid = vec![vec![" ".to_owned(); columns]; rows];
+    let mut put = |position: i32, mark: &str| {
+        let angle = TAU * f64::from(position) / f64::from(dial.size);
+        let row = RADIUS - RADIUS * angle.cos();
+        let column = 2.0 * (RADIUS + RADIUS * angle.sin());
+        grid[row.round() as usize][column.round() as usize] = mark.to_owned();
+    };
+
+    for position in 0..dial.size {
+        put(position, ".");
+    }
+    put(dial.target, "o");
+    let pointer = match (ansi, frame.position == dial.target) {
+        (false, _) => "@".to_owned(),
+        (true, false) => "\x1b[1;33m@\x1b[0m".to_owned(),
+        (true, true) => "\x1b[1;32m@\x1b[0m".to_owned(),
+    };
+    put(frame.position, &pointer);
+
+    let number = frame.position.to_string();
+    let start = (columns - number.len()) / 2;
+    for (i, digit) in number.chars().enumerate() {
+        grid[rows / 2][start + i] = digit.to_string();
+    }
+
+    let mut out = String::new();
+    for row in grid {
+        writeln!(out, "{}", row.concat().trim_end()).unwrap();
+    }
+
+    let direction = if frame.offset < 0 { 'L' } else { 'R' };
+    let rotation = match frame.rotation {
+        0 => "-".to_owned(),
+        _ => format!("{direction}{}", frame.offset.abs()),
+    };
+    write!(
+        out,
+        "\nrotation {}/{total} {rotation:<6} passes {:<6} landings {}",
+        frame.rotation, frame.passes, frame.landings
+    )
+    .unwrap();
+    match (frame.landed, ansi) {
+        (true, true) => out.push_str("  \x1b[1;32mlanded!\x1b[0m"),
+        (true, false) => out.push_str("  landed!"),
+        (false, _) => {}
+    }
+    out.push('\n');
+
+    out
+}
+
+/// Hides the terminal cursor until dropped, so it comes back even when drawing fails part way
+struct HiddenCursor<'a, W: Write>(&'a mut W);
+
+impl<'a, W: Write> HiddenCursor<'a, W> {
+    /// Also clears the screen, for frames to be drawn over each other from the top
+    fn new(out: &'a mut W) -> Result<Self> {
+        write!(out, "\x1b[2J\x1b[?25l")?;
+        Ok(Self(out))
+    }
+}
+
+impl<W: Write> Drop for HiddenCursor<'_, W> {
+    fn drop(&mut self) {
+        let _ = write!(self.0, "\x1b[?25h");
+        let _ = self.0.flush();
+    }
+}
+
+/// Animate `rotations` on `dial` into `out`, turning `speed` clicks a second
+///
+/// With `ansi` every frame is drawn over the last, otherwise they're written one after another.
+pub fn play(
+    dial: Dial,
+    rotations: &[i32],
+    speed: f64,
+    ansi: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    if !(speed.is_finite() && speed > 0.0) {
+        bail!("Speed should be a positive number of clicks a second, got {speed}");
+    }
+
+    // Fewer frames than clicks when going fast, each showing the dial as long as its clicks take
+    let clicks_per_frame = (speed / MAX_FPS).ceil().min(f64::from(u32::MAX)) as u32;
+    let frame_time = Duration::from_secs_f64(f64::from(clicks_per_frame) / speed);
+
+    let mut screen = None;
+    let out: &mut dyn Write = match ansi {
+        true => screen.insert(HiddenCursor::new(out)?).0,
+        false => out,
+    };
+
+    for (i, frame) in frames(dial, rotations, clicks_per_frame).enumerate() {
+        let picture = draw(dial, &frame, rotations.len(), ansi);
+        match (ansi, i) {
+            (true, _) => write!(out, "\x1b[H{picture}")?,
+            (false, 0) => write!(out, "{picture}")?,
+            (false, _) => write!(out, "\n{picture}")?,
+        }
+        out.flush()?;
+
+        let hold = if frame.landed {
+            LANDING_HOLD_FRAMES
+        } else {
+            1.0
+        };
+        thread::sleep(frame_time.mul_f64(hold));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day01::{SAMPLES, parse};
+
+    #[test]
+    fn test_frames() {
+        let rotations = parse(SAMPLES[0].1).unwrap();
+        let dial = Dial::PUZZLE;
+
+        for clicks_per_frame in [1, 7, 1000] {
+            let frames = frames(dial, &rotations, clicks_per_frame).collect::<Vec<_>>();
+            let last = frames.last().unwrap();
+            assert_eq!(last.passes, dial.count_passes(&rotations));
+            assert_eq!(last.landings, dial.count_landings(&rotations));
+
+            // The last frame of each rotation ends where the trace does
+            let ends = frames
+                .windows(2)
+                .filter(|pair| pair[0].rotation != pair[1].rotation)
+                .map(|pair| (pair[0].position, pair[0].landed))
+                .skip(1)
+                .chain([(last.position, last.landed)]);
+            let steps = dial.trace(&rotations).map(|step| (step.end, step.landed));
+            assert!(ends.eq(steps));
+        }
+
+        // One frame per click, plus the one before
+        let clicks = rotations
+            .iter()
+            .map(|offset| offset.unsigned_abs())
+            .sum::<u32>();
+        assert_eq!(frames(dial, &rotations, 1).count(), clicks as usize + 1);
+    }
+
+    #[test]
+    fn test_draw() {
+        let rotations = parse(SAMPLES[0].1).unwrap();
+        let frame = frames(Dial::PUZZLE, &rotations, 1000)
+            .find(|frame| frame.landed)
+            .unwrap();
+
+        let picture = draw(Dial::PUZZLE, &frame, rotations.len(), false);
+        let lines = picture.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "                  . ... @ ... .");
+        assert_eq!(
+            lines[12].trim(),
+            ".                       0                       ."
+        );
+        assert_eq!(lines[24], "                  . ... . ... .");
+        assert_eq!(
+            lines.last().unwrap(),
+            &"rotation 3/10 R48    passes 2      landings 1  landed!"
+        );
+
+        let picture = draw(Dial::PUZZLE, &frame, rotations.len(), true);
+        assert!(picture.contains("\x1b[1;32m@\x1b[0m"));
+        assert!(picture.ends_with("\x1b[1;32mlanded!\x1b[0m\n"));
+    }
+
+    /// Takes `room` bytes, then fails every write after
+    struct Full {
+        written: Vec<u8>,
+        room: usize,
+    }
+
+    impl Write for Full {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.written.len() + buf.len() > self.room {
+                return Err(std::io::ErrorKind::StorageFull.into());
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let rotations = parse(SAMPLES[0].1).unwrap();
+
+        let mut out = Vec::new();
+        play(Dial::PUZZLE, &rotations, 1e15, false, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains('\x1b'));
+        assert!(out.ends_with("landings 3\n"));
+
+        // The cursor comes back even when the output fills up part way through
+        let mut out = Full {
+            written: Vec::new(),
+            room: 5000,
+        };
+        assert!(play(Dial::PUZZLE, &rotations, 1e15, true, &mut out).is_err());
+        let out = String::from_utf8(out.written).unwrap();
+        assert!(out.starts_with("\x1b[2J\x1b[?25l"));
+        assert!(out.ends_with("\x1b[?25h"));
+    }
+}
@@ -1,81 +1,94 @@
-use color_eyre::eyre::{Result, bail};
-
-use crate::fetch_input;
-
-/// Parse line into offset
-fn parse_line(line: &str) -> Result<i32> {
-    let (dir, offset) = line.split_at(1);
-    let offset = offset.parse::<i32>()?;
+use color_eyre::eyre::Result;
+
+use crate::day01::{self, Dial};
+
+/// The rotation at the start of `bytes` and the length of its line, line break included, if
+/// it's written the usual way
+#[inline]
+pub(crate) fn read_plain(bytes: &[u8]) -> Option<(i32, usize)> {
+    let (&direction, tail) = bytes.split_first()?;
+
+    // Parses as many digits as there are, so there's no need to find the line end first
+    let (clicks, len) = atoi_simd::parse_any_pos::<i32>(tail).ok()?;
+    let offset = match direction {
+        b'L' => -clicks,
+        b'R' => clicks,
+        _ => return None,
+    };
 
-    let offset = match dir {
-        "L" => -offset,
-        "R" => offset,
-        _ => bail!("Invalid direction"),
+    let line_break = match &tail[len..] {
+        [b'\n', ..] => 1,
+        [b'\r', b'\n', ..] => 2,
+        [] => 0,
+        _ => return None,
     };
 
-    Ok(offset)
+    Some((offset, 1 + len + line_break))
 }
 
-fn part_1(input: &str) -> i32 {
-    let mut dial = 50;
-    let mut count = 0;
-
-    for line in input.lines() {
-        let offset = parse_line(line).unwrap();
-
-        dial = (dial + offset).rem_euclid(100);
+/// Leave the line at `start` to [`day01::parse_line`], which accepts a little more and explains
+/// what's wrong otherwise
+#[cold]
+pub(crate) fn read_unusual(
+    input: &[u8],
+    start: usize,
+    skip_blank: bool,
+) -> Result<(Option<i32>, usize)> {
+    let rest = &input[start..];
+    let len = rest
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(rest.len(), |i| i + 1);
+
+    let line = String::from_utf8_lossy(&rest[..len]);
+    // Line breaks the way `str::lines` sees them
+    let line = match line.strip_suffix('\n') {
+        Some(line) => line.strip_suffix('\r').unwrap_or(line),
+        None => &line,
+    };
 
-        if dial == 0 {
-            count += 1;
-        }
+    if skip_blank && line.trim().is_empty() {
+        return Ok((None, len));
     }
 
-    count
+    let number = input[..start].iter().filter(|&&b| b == b'\n').count() + 1;
+    Ok((Some(day01::parse_numbered(number, line)?), len))
 }
 
-fn part_2(input: &str) -> u32 {
-    let mut dial = 50;
-    let mut count = 0;
-
-    for line in input.lines() {
-        let offset = parse_line(line).unwrap();
-
-        let new_dial_unwrapped = dial + offset;
-
-        if offset > 0 {
-            count += (dial + offset) as u32 / 100;
-        } else if offset < 0 {
-            count += i32::abs_diff((dial).div_ceil(100), (new_dial_unwrapped).div_ceil(100));
+/// Both answers for `dial` from one pass over the raw bytes, without building any lines or
+/// strings
+pub fn solve(input: &[u8], dial: Dial, skip_blank: bool) -> Result<(u64, u64)> {
+    let mut dial = dial;
+    let (mut landed, mut passed) = (0, 0);
+    let mut start = 0;
+
+    while start < input.len() {
+        let (offset, len) = match read_plain(&input[start..]) {
+            Some((offset, len)) => (Some(offset), len),
+            None => read_unusual(input, start, skip_blank)?,
+        };
+        start += len;
+
+        if let Some(offset) = offset {
+            passed += u64::from(dial.turn_counting_passes(offset));
+            landed += u64::from(dial.position == dial.target);
         }
-
-        dial = new_dial_unwrapped.rem_euclid(100);
     }
 
-    count
+    Ok((landed, passed))
 }
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(1)?;
-
-    let start = std::time::Instant::now();
-    let part_1 = part_1(&input);
-    println!("Part 1 took {:?}", start.elapsed());
-
-    let start = std::time::Instant::now();
-    let part_2 = part_2(&input);
-    println!("Part 2 took {:?}", start.elapsed());
-    dbg!(part_1, part_2);
-    assert_eq!(part_1, 1147);
-    assert_eq!(part_2, 6789);
-
-    Ok(())
+/// Count how many rotations leave the dial pointing at 0
+pub fn part_1(input: &str) -> Result<u64> {
+    Ok(solve(input.as_bytes(), Dial::PUZZLE, false)?.0)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Count how many times the dial points at 0, including while rotating
+pub fn part_2(input: &str) -> Result<u64> {
+    Ok(solve(input.as_bytes(), Dial::PUZZLE, false)?.1)
+}
 
-    const SAMPLE_INPUT: &str = "L68
+const SAMPLE_INPUT: &str = "L68
 L30
 R48
 L5
@@ -87,19 +100,125 @@ R14
 L82
 ";
 
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        hint::black_box,
+        time::{Duration, Instant},
+    };
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::{day01, day01_generator, fetch_input, oracle};
+
     #[test]
     fn test_part_1() {
         let real_input = fetch_input(1).unwrap();
 
-        assert_eq!(part_1(SAMPLE_INPUT), 3);
-        assert_eq!(part_1(&real_input), 1147);
+        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 3);
+        assert_eq!(part_1(&real_input).unwrap(), 1147);
     }
 
     #[test]
     fn test_part_2() {
         let real_input = fetch_input(1).unwrap();
 
-        assert_eq!(part_2(SAMPLE_INPUT), 6);
-        assert_eq!(part_2(&real_input), 6789);
+        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 6);
+        assert_eq!(part_2(&real_input).unwrap(), 6789);
+    }
+
+    /// The fast version should agree with the reference one, itself checked by brute force
+    #[test]
+    fn test_matches_day01() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(1..=120);
+                let dial =
+                    Dial::new(size, rng.random_range(0..size), rng.random_range(0..size)).unwrap();
+
+                (dial, day01_generator::random_input(rng, 50))
+            },
+            |&(dial, ref input)| {
+                let rotations = day01::parse(input).unwrap();
+
+                let expected = (
+                    dial.count_landings(&rotations),
+                    dial.count_passes(&rotations),
+                );
+
+                let solve = |input: &str| solve(input.as_bytes(), dial, false).unwrap();
+
+                assert_eq!(solve(input), expected);
+                assert_eq!(solve(input.trim_end()), expected);
+                assert_eq!(solve(&input.replace('\n', "\r\n")), expected);
+            },
+        );
+    }
+
+    /// Unusual lines should be read, or rejected, exactly like the reference version does
+    #[test]
+    fn test_unusual_lines() {
+        for input in [
+            "L1\n\nR2\n",
+            "L1\n  \r\nR2\n",
+            "L1\nR2\néR3\n",
+            "R-5\n",
+            "L\n",
+            "R99999999999\n",
+            "L0005\nR2x\n",
+            "L5\r",
+        ] {
+            for skip_blank in [false, true] {
+                let expected = day01::parse_with(input, skip_blank).map(|rotations| {
+                    let dial = Dial::PUZZLE;
+                    (
+                        dial.count_landings(&rotations),
+                        dial.count_passes(&rotations),
+                    )
+                });
+
+                match (solve(input.as_bytes(), Dial::PUZZLE, skip_blank), expected) {
+                    (Ok(answers), Ok(expected)) => assert_eq!(answers, expected, "{input:?}"),
+                    (Err(error), Err(expected)) => {
+                        assert_eq!(format!("{error:#}"), format!("{expected:#}"), "{input:?}")
+                    }
+                    (answers, expected) => panic!("{input:?}: {answers:?} but {expected:?}"),
+                }
+            }
+        }
+    }
+
+    /// Best of several runs, so a busy machine doesn't decide the result
+    fn fastest<T>(mut f: impl FnMut() -> T) -> Duration {
+        (0..20)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(f());
+                start.elapsed()
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    #[ignore = "timing, run with `cargo test --release -- --ignored`"]
+    fn bench_against_day01() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let input = (0..200)
+            .map(|_| day01_generator::random_input(&mut rng, 50))
+            .collect::<String>();
+
+        let reference = fastest(|| {
+            let rotations = day01::parse(black_box(&input)).unwrap();
+            (day01::part_1(&rotations), day01::part_2(&rotations))
+        });
+        let fast = fastest(|| solve(black_box(input.as_bytes()), Dial::PUZZLE, false));
+
+        println!("day01 {reference:?}, day01_fast {fast:?}");
+        assert!(fast < reference);
     }
 }
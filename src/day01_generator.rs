@@ -0,0 +1,236 @@
+//! Rotations for day 1 with known answers, for regression cases and stress tests
+
+use color_eyre::eyre::{Result, bail, eyre};
+use rand::{Rng, distr::Distribution};
+
+use crate::day01::Dial;
+
+/// How many tries a sampled rotation gets to fit the plan before one is made to fit
+const SAMPLES_PER_ROTATION: usize = 16;
+
+/// The answers the rotations should have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Targets {
+    pub rotations: usize,
+    /// Rotations that stop at the target, the part 1 answer
+    pub landings: u64,
+    /// Clicks that point at the target, the part 2 answer, at least one per landing
+    pub passes: u64,
+}
+
+/// Rotations turning `dial` that hit `targets` exactly
+///
+/// Magnitudes are drawn from `magnitudes` as long as they keep the targets reachable, with both
+/// landings and passes spread out over the whole list. A rotation that has to do something
+/// specific gets the smallest magnitude that does it, which may fall outside the distribution.
+pub fn generate(
+    rng: &mut impl Rng,
+    dial: Dial,
+    magnitudes: &impl Distribution<u32>,
+    targets: Targets,
+) -> Result<Vec<i32>> {
+    if dial.size < 3 {
+        bail!("Dial needs at least 3 positions to be able to miss the target");
+    }
+    if targets.landings as usize > targets.rotations {
+        bail!(
+            "Can't land {} times in {} rotations",
+            targets.landings,
+            targets.rotations
+        );
+    }
+    if targets.passes < targets.landings {
+        bail!("Every landing passes the target too, so passes should be at least the landings");
+    }
+    if targets.rotations == 0 && targets.passes > 0 {
+        bail!("Can't pass the target without any rotations");
+    }
+
+    let mut dial = dial;
+    let (mut landings, mut passes) = (targets.landings, targets.passes);
+    let mut rotations = Vec::with_capacity(targets.rotations);
+
+    for left in (1..=targets.rotations).rev() {
+        // Land with the odds that spread the remaining landings evenly
+        let land = rng.random_range(0..left) < landings as usize;
+
+        // Passes the rest of the rotations still need, and the most this one can take
+        let rest_needs = landings - u64::from(land);
+        let most = passes - rest_needs;
+        let least = if left == 1 { passes } else { u64::from(land) };
+        let fair_share = passes.div_ceil(left as u64).clamp(least, most);
+
+        let sampled = (0..SAMPLES_PER_ROTATION).find_map(|_| {
+            let clicks = magnitudes.sample(rng).clamp(1, i32::MAX as u32) as i32;
+            let offset = if rng.random_bool(0.5) {
+                clicks
+            } else {
+                -clicks
+            };
+
+            let mut turned = dial;
+            let passed = u64::from(turned.turn_counting_passes(offset));
+            let landed = turned.position == turned.target;
+
+            // Near the fair share, so the last rotations aren't left to catch up
+            let fits = landed == land
+                && (least..=most).contains(&passed)
+                && passed.abs_diff(fair_share) <= fair_share / 2 + 1;
+            fits.then_some(offset)
+        });
+
+        let offset = match sampled {
+            Some(offset) => offset,
+            // No one rotation passes more than a `u32` worth, so beyond that `construct` fails
+            None => construct(rng, dial, land, fair_share.try_into().unwrap_or(u32::MAX))?,
+        };
+
+        passes -= u64::from(dial.turn_counting_passes(offset));
+        landings -= u64::from(dial.position == dial.target);
+        rotations.push(offset);
+    }
+
+    Ok(rotations)
+}
+
+/// A rotation from where `dial` is that passes the target exactly `passes` times, ending on it
+/// with `land`
+fn construct(rng: &mut impl Rng, dial: Dial, land: bool, passes: u32) -> Result<i32> {
+    let size = i64::from(dial.size);
+    let passes = i64::from(passes);
+
+    // Clicks until the target comes round in each direction, a full turn when already on it
+    let until = |ahead: i32| match i64::from(ahead).rem_euclid(size) {
+        0 => size,
+        clicks => clicks,
+    };
+    let (right, left) = (
+        until(dial.target - dial.position),
+        until(dial.position - dial.target),
+    );
+
+    let (clicks, sign) = match (land, passes) {
+        (true, 0) => unreachable!("landing passes the target"),
+        (true, passes) => (right + (passes - 1) * size, 1),
+        // Stop anywhere short of the next pass, in whichever direction leaves room
+        (false, 0) if right > 1 => (rng.random_range(1..right), 1),
+        (false, 0) => (rng.random_range(1..left), -1),
+        (false, passes) => {
+            let first = right + (passes - 1) * size;
+            (rng.random_range(first + 1..first + size), 1)
+        }
+    };
+
+    let offset = i32::try_from(clicks * sign)
+        .map_err(|_| eyre!("Rotation of {clicks} clicks is too large"))?;
+    Ok(offset)
+}
+
+/// One rotation per line, like the puzzle input
+pub fn render(rotations: &[i32]) -> String {
+    rotations
+        .iter()
+        .map(|&offset| {
+            let direction = if offset < 0 { 'L' } else { 'R' };
+            format!("{direction}{}\n", offset.unsigned_abs())
+        })
+        .collect()
+}
+
+/// Input of 1 to `max` rotations up to 350 clicks either way, for checking solutions against
+/// each other
+#[cfg(test)]
+pub(crate) fn random_input(rng: &mut impl Rng, max: usize) -> String {
+    let rotations = (0..rng.random_range(1..max))
+        .map(|_| rng.random_range(1..=350) * if rng.random_bool(0.5) { -1 } else { 1 })
+        .collect::<Vec<_>>();
+
+    render(&rotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, distr::Uniform, rngs::StdRng};
+
+    use super::*;
+    use crate::{day01, oracle};
+
+    #[test]
+    fn test_hits_targets() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(3..=120);
+                let dial =
+                    Dial::new(size, rng.random_range(0..size), rng.random_range(0..size)).unwrap();
+
+                let rotations = rng.random_range(0..60);
+                let landings = rng.random_range(0..=rotations as u64);
+                let passes = match rotations {
+                    0 => 0,
+                    _ => landings + rng.random_range(0..200),
+                };
+                let magnitudes = Uniform::new_inclusive(1, rng.random_range(1..=400)).unwrap();
+                let targets = Targets {
+                    rotations,
+                    landings,
+                    passes,
+                };
+
+                (dial, magnitudes, targets, rng.random())
+            },
+            |&(dial, ref magnitudes, targets, seed)| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let rotations = generate(&mut rng, dial, magnitudes, targets).unwrap();
+                let input = render(&rotations);
+
+                let parsed = day01::parse(&input).unwrap();
+                assert_eq!(parsed, rotations);
+                assert_eq!(parsed.len(), targets.rotations);
+                assert_eq!(dial.count_landings(&parsed), targets.landings);
+                assert_eq!(dial.count_passes(&parsed), targets.passes);
+            },
+        );
+    }
+
+    #[test]
+    fn test_follows_magnitudes() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let magnitudes = Uniform::new_inclusive(1, 350).unwrap();
+        let targets = Targets {
+            rotations: 4000,
+            landings: 40,
+            passes: 7000,
+        };
+
+        let rotations = generate(&mut rng, Dial::PUZZLE, &magnitudes, targets).unwrap();
+        assert_eq!(day01::part_1(&rotations).unwrap(), 40);
+        assert_eq!(day01::part_2(&rotations).unwrap(), 7000);
+
+        // Targets close to what the distribution gives anyway need few made up rotations
+        let outside = rotations.iter().filter(|offset| offset.abs() > 350).count();
+        assert!(
+            outside < 100,
+            "{outside} rotations outside the distribution"
+        );
+    }
+
+    #[test]
+    fn test_impossible_targets() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let magnitudes = Uniform::new_inclusive(1, 350).unwrap();
+        let targets = |rotations, landings, passes| Targets {
+            rotations,
+            landings,
+            passes,
+        };
+
+        for (dial, targets) in [
+            (Dial::PUZZLE, targets(2, 3, 3)),
+            (Dial::PUZZLE, targets(2, 2, 1)),
+            (Dial::PUZZLE, targets(0, 0, 1)),
+            (Dial::new(2, 0, 0).unwrap(), targets(2, 0, 0)),
+        ] {
+            assert!(generate(&mut rng, dial, &magnitudes, targets).is_err());
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use color_eyre::eyre::Result;
+use rayon::prelude::*;
+
+use crate::{
+    day01::{self, Dial},
+    day01_fast::{read_plain, read_unusual},
+};
+
+/// Chunks smaller than this aren't worth handing to another thread
+const MIN_CHUNK_BYTES: usize = 1 << 16;
+
+/// What a run of rotations does, for every position the dial could start it in
+///
+/// Rotations compose, so summaries of consecutive runs combine into the summary of both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Summary {
+    /// Clicks to the right overall, modulo the dial size
+    net: usize,
+    /// Stops at the target, by start position
+    landed: Vec<u64>,
+    /// Clicks that pointed at the target, by start position
+    passed: Vec<u64>,
+}
+
+impl Summary {
+    /// No rotations at all
+    fn identity(size: usize) -> Self {
+        Self {
+            net: 0,
+            landed: vec![0; size],
+            passed: vec![0; size],
+        }
+    }
+
+    /// `self` followed by `next`
+    fn then(&self, next: &Self) -> Self {
+        let size = self.landed.len();
+        let after = |start: usize| (start + self.net) % size;
+
+        Self {
+            net: (self.net + next.net) % size,
+            landed: (0..size)
+                .map(|start| self.landed[start] + next.landed[after(start)])
+                .collect(),
+            passed: (0..size)
+                .map(|start| self.passed[start] + next.passed[after(start)])
+                .collect(),
+        }
+    }
+}
+
+/// Builds a [`Summary`] one rotation at a time, in time independent of the dial size
+struct Summarizer {
+    size: i64,
+    target: i64,
+    /// Net clicks so far, modulo the size
+    net: i64,
+    landed: Vec<u64>,
+    /// Passes every start position gets, from full turns
+    full_turns: u64,
+    /// Differences between the extra passes of neighbouring start positions
+    extra: Vec<i64>,
+}
+
+impl Summarizer {
+    fn new(dial: Dial) -> Self {
+        let size = dial.size as usize;
+
+        Self {
+            size: dial.size.into(),
+            target: dial.target.into(),
+            net: 0,
+            landed: vec![0; size],
+            full_turns: 0,
+            extra: vec![0; size + 1],
+        }
+    }
+
+    /// One more pass for `len` start positions from `first`, wrapping around
+    fn add_extra(&mut self, first: i64, len: i64) {
+        let first = first.rem_euclid(self.size) as usize;
+        let end = first + len as usize;
+        let size = self.size as usize;
+
+        self.extra[first] += 1;
+        if end <= size {
+            self.extra[end] -= 1;
+        } else {
+            self.extra[size] -= 1;
+            self.extra[0] += 1;
+            self.extra[end - size] -= 1;
+        }
+    }
+
+    fn turn(&mut self, offset: i32) {
+        let (size, target, before) = (self.size, self.target, self.net);
+        let clicks = i64::from(offset).abs();
+
+        // Like `Dial::turn_counting_passes`, except the start positions that pass the target in
+        // the last part turn are a range of them rather than a yes or no
+        self.full_turns += (clicks / size) as u64;
+        let rest = clicks % size;
+        if rest > 0 {
+            if offset < 0 {
+                self.add_extra(target - before + 1, rest);
+            } else {
+                self.add_extra(target - before - rest, rest);
+            }
+        }
+
+        self.net = (before + i64::from(offset)).rem_euclid(size);
+        self.landed[(target - self.net).rem_euclid(size) as usize] += 1;
+    }
+
+    fn finish(self) -> Summary {
+        let passed = self
+            .extra
+            .iter()
+            .take(self.size as usize)
+            .scan(0, |extra, difference| {
+                *extra += difference;
+                Some(self.full_turns + *extra as u64)
+            })
+            .collect();
+
+        Summary {
+            net: self.net as usize,
+            landed: self.landed,
+            passed,
+        }
+    }
+}
+
+/// Where to split `input` into about `chunks` runs of whole lines
+fn boundaries(input: &[u8], chunks: usize) -> Vec<usize> {
+    let mut boundaries = vec![0];
+
+    for i in 1..chunks {
+        let guess = (input.len() * i / chunks).max(*boundaries.last().unwrap());
+        let boundary = input[guess..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(input.len(), |newline| guess + newline + 1);
+
+        if boundary > *boundaries.last().unwrap() {
+            boundaries.push(boundary);
+        }
+    }
+
+    boundaries.push(input.len());
+    boundaries.dedup();
+    boundaries
+}
+
+/// Summarize the lines from `start` to `end`, which should be line boundaries
+fn summarize(
+    input: &[u8],
+    start: usize,
+    end: usize,
+    dial: Dial,
+    skip_blank: bool,
+) -> Result<Summary> {
+    let mut summarizer = Summarizer::new(dial);
+    let mut position = start;
+
+    while position < end {
+        let (offset, len) = match read_plain(&input[position..end]) {
+            Some((offset, len)) => (Some(offset), len),
+            None => read_unusual(input, position, skip_blank)?,
+        };
+        position += len;
+
+        if let Some(offset) = offset {
+            summarizer.turn(offset);
+        }
+    }
+
+    Ok(summarizer.finish())
+}
+
+/// Both answers for `dial`, summarizing about `chunks` runs of lines in parallel
+pub fn solve_in_chunks(
+    input: &[u8],
+    dial: Dial,
+    skip_blank: bool,
+    chunks: usize,
+) -> Result<(u64, u64)> {
+    let boundaries = boundaries(input, chunks.max(1));
+    let size = dial.size as usize;
+
+    let summaries = boundaries
+        .par_windows(2)
+        .map(|window| summarize(input, window[0], window[1], dial, skip_blank))
+        .collect::<Vec<_>>();
+
+    // Combined in input order, so the summary follows the whole input and the error
+    // reported is the first bad line rather than whichever chunk failed first
+    let summary = summaries
+        .into_iter()
+        .try_fold(Summary::identity(size), |a, b| b.map(|b| a.then(&b)))?;
+
+    let start = dial.position as usize;
+    Ok((summary.landed[start], summary.passed[start]))
+}
+
+/// Both answers for `dial`, with as many chunks as keeps every thread busy
+pub fn solve(input: &[u8], dial: Dial, skip_blank: bool) -> Result<(u64, u64)> {
+    let chunks = (input.len() / MIN_CHUNK_BYTES).clamp(1, rayon::current_num_threads() * 4);
+    solve_in_chunks(input, dial, skip_blank, chunks)
+}
+
+/// Count how many rotations leave the dial pointing at 0
+pub fn part_1(input: &str) -> Result<u64> {
+    Ok(solve(input.as_bytes(), Dial::PUZZLE, false)?.0)
+}
+
+/// Count how many times the dial points at 0, including while rotating
+pub fn part_2(input: &str) -> Result<u64> {
+    Ok(solve(input.as_bytes(), Dial::PUZZLE, false)?.1)
+}
+
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = day01::SAMPLES;
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::{day01_fast, day01_generator, oracle};
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part_1(day01::SAMPLES[0].1).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(part_2(day01::SAMPLES[0].1).unwrap(), 6);
+    }
+
+    /// Any split into chunks should agree with the sequential version, on any dial
+    #[test]
+    fn test_matches_day01() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(1..=120);
+                let dial =
+                    Dial::new(size, rng.random_range(0..size), rng.random_range(0..size)).unwrap();
+
+                (
+                    dial,
+                    rng.random_range(1..20),
+                    day01_generator::random_input(rng, 200),
+                )
+            },
+            |&(dial, chunks, ref input)| {
+                let rotations = day01::parse(input).unwrap();
+                let expected = (
+                    dial.count_landings(&rotations),
+                    dial.count_passes(&rotations),
+                );
+
+                let answers = solve_in_chunks(input.as_bytes(), dial, false, chunks).unwrap();
+                assert_eq!(answers, expected);
+            },
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        let input = "L1\nR2\n".repeat(1000) + "X3\n" + &"L1\n".repeat(1000) + "Y4\n";
+
+        // However the chunks split and whichever finishes first, the earlier bad line is reported
+        for chunks in 1..16 {
+            let error = solve_in_chunks(input.as_bytes(), Dial::PUZZLE, false, chunks).unwrap_err();
+            assert_eq!(
+                format!("{error:#}"),
+                "Line 2001: \"X3\": Unknown direction 'X', expected L or R"
+            );
+        }
+    }
+
+    /// Answers too big for a `u32`, from a few kilobytes of huge rotations
+    #[test]
+    fn test_counts_beyond_u32() {
+        let input = "R2147483647\n".repeat(300);
+        let rotations = day01::parse(&input).unwrap();
+
+        let expected = day01_fast::solve(input.as_bytes(), Dial::PUZZLE, false).unwrap();
+        assert!(expected.1 > u32::MAX.into());
+        assert_eq!(expected.1, Dial::PUZZLE.count_passes(&rotations));
+        assert_eq!(
+            solve_in_chunks(input.as_bytes(), Dial::PUZZLE, false, 4).unwrap(),
+            expected
+        );
+    }
+
+    /// About `DAY01_PARALLEL_BYTES` of rotations, a gigabyte by default
+    #[test]
+    #[ignore = "slow and memory hungry, run with `cargo test --release -- --ignored`"]
+    fn test_huge_input() {
+        let bytes = std::env::var("DAY01_PARALLEL_BYTES")
+            .map(|bytes| {
+                bytes
+                    .parse()
+                    .expect("DAY01_PARALLEL_BYTES should be a number")
+            })
+            .unwrap_or(1 << 30);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut input = Vec::with_capacity(bytes + 8);
+        while input.len() < bytes {
+            let direction = if rng.random_bool(0.5) { 'L' } else { 'R' };
+            writeln!(input, "{direction}{}", rng.random_range(1..=350)).unwrap();
+        }
+
+        let expected = day01_fast::solve(&input, Dial::PUZZLE, false).unwrap();
+        assert_eq!(solve(&input, Dial::PUZZLE, false).unwrap(), expected);
+    }
+}
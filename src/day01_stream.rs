@@ -0,0 +1,223 @@
+use std::io::{BufRead, BufReader, Read};
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+
+use crate::day01::{self, Dial};
+
+/// Bytes read from the source at a time
+const BUFFER_BYTES: usize = 1 << 16;
+
+/// Most of a line held at once, far more than any magnitude that fits needs
+///
+/// Longer lines are shortened as they're read if they could still be rotations, with padding
+/// zeros, or blank lines to skip.
+const MAX_LINE_BYTES: usize = 64;
+
+/// Shorten the start of a long line without changing what [`day01::parse_line`] makes of it,
+/// returning whether it fits in [`MAX_LINE_BYTES`] after
+fn shorten(line: &mut Vec<u8>, skip_blank: bool) -> bool {
+    // Padding zeros in front of the magnitude, keeping the last digit in case it's all there is
+    if let [b'L' | b'R', magnitude @ ..] = &line[..] {
+        let digits = magnitude.iter().take_while(|b| b.is_ascii_digit()).count();
+        let zeros = magnitude.iter().take_while(|&&b| b == b'0').count();
+        line.drain(1..1 + zeros.min(digits.saturating_sub(1)));
+    } else if skip_blank {
+        // Whitespace so far, leaving a character cut off at the end for the next read to finish
+        let text = match std::str::from_utf8(line) {
+            Ok(text) => text,
+            Err(e) if e.error_len().is_none() => {
+                std::str::from_utf8(&line[..e.valid_up_to()]).unwrap_or_default()
+            }
+            Err(_) => "",
+        };
+        let blank = if text.trim().is_empty() {
+            text.len()
+        } else {
+            0
+        };
+        line.drain(..blank);
+    }
+
+    line.len() <= MAX_LINE_BYTES
+}
+
+/// Both answers for `dial`, reading one line at a time so memory stays bounded however long
+/// `reader` goes on
+pub fn solve(reader: impl Read, dial: Dial, skip_blank: bool) -> Result<(u64, u64)> {
+    let mut reader = BufReader::with_capacity(BUFFER_BYTES, reader);
+    let mut line = Vec::with_capacity(MAX_LINE_BYTES + 1);
+    let mut dial = dial;
+    let (mut landed, mut passed) = (0, 0);
+
+    for number in 1.. {
+        line.clear();
+        let mut total = 0;
+        loop {
+            let room = MAX_LINE_BYTES + 1 - line.len();
+            let read = (&mut reader)
+                .take(room as u64)
+                .read_until(b'\n', &mut line)
+                .wrap_err_with(|| format!("Failed to read line {number}"))?;
+            total += read;
+
+            if read == 0 || line.last() == Some(&b'\n') {
+                break;
+            }
+            if line.len() > MAX_LINE_BYTES && !shorten(&mut line, skip_blank) {
+                bail!("Line {number} is longer than {MAX_LINE_BYTES} bytes and isn't a rotation");
+            }
+        }
+        if total == 0 {
+            break;
+        }
+
+        // Line endings as `str::lines` takes them
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+        }
+
+        let line =
+            std::str::from_utf8(&line).map_err(|_| eyre!("Line {number} isn't valid UTF-8"))?;
+        if skip_blank && line.trim().is_empty() {
+            continue;
+        }
+
+        let offset = day01::parse_numbered(number, line)?;
+        passed += u64::from(dial.turn_counting_passes(offset));
+        landed += u64::from(dial.position == dial.target);
+    }
+
+    Ok((landed, passed))
+}
+
+/// Count how many rotations leave the dial pointing at 0
+pub fn part_1(reader: impl Read) -> Result<u64> {
+    Ok(solve(reader, Dial::PUZZLE, false)?.0)
+}
+
+/// Count how many times the dial points at 0, including while rotating
+pub fn part_2(reader: impl Read) -> Result<u64> {
+    Ok(solve(reader, Dial::PUZZLE, false)?.1)
+}
+
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = day01::SAMPLES;
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::{day01_generator, oracle};
+
+    /// Hands out what it holds a few bytes at a time, to split lines between reads
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        rng: StdRng,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self
+                .rng
+                .random_range(1..=7)
+                .min(buf.len())
+                .min(self.bytes.len());
+            let (now, later) = self.bytes.split_at(len);
+            buf[..len].copy_from_slice(now);
+            self.bytes = later;
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part_1(day01::SAMPLES[0].1.as_bytes()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(part_2(day01::SAMPLES[0].1.as_bytes()).unwrap(), 6);
+    }
+
+    #[test]
+    fn test_matches_day01() {
+        oracle::check(
+            |rng| {
+                let size = rng.random_range(1..=120);
+                let dial =
+                    Dial::new(size, rng.random_range(0..size), rng.random_range(0..size)).unwrap();
+                let rotations = (0..rng.random_range(0..200))
+                    .map(|_| rng.random_range(-350..=350))
+                    .collect::<Vec<_>>();
+
+                (dial, day01_generator::render(&rotations), rng.random())
+            },
+            |&(dial, ref input, seed)| {
+                let rotations = day01::parse(input).unwrap();
+                let expected = (
+                    dial.count_landings(&rotations),
+                    dial.count_passes(&rotations),
+                );
+
+                let reader = Trickle {
+                    bytes: input.as_bytes(),
+                    rng: StdRng::seed_from_u64(seed),
+                };
+                assert_eq!(solve(reader, dial, false).unwrap(), expected);
+            },
+        );
+    }
+
+    #[test]
+    fn test_unusual_lines() {
+        let solve = |input: &str, skip_blank| solve(input.as_bytes(), Dial::PUZZLE, skip_blank);
+
+        assert_eq!(solve("L50\r\nR100\r\nL0", false).unwrap(), (3, 2));
+        assert_eq!(solve("L50\n\n  \nR100\n", true).unwrap(), (2, 2));
+        assert_eq!(solve("", false).unwrap(), (0, 0));
+
+        let error = |input: &str| format!("{:#}", solve(input, false).unwrap_err());
+        assert_eq!(error("L1\n\nR2\n"), "Line 2: \"\": Empty line");
+        assert_eq!(
+            error("L1\nR2\r"),
+            "Line 2: \"R2\\r\": Bad magnitude \"2\\r\", expected digits"
+        );
+        assert_eq!(
+            error(&format!("L1\nR{}\n", "9".repeat(100))),
+            "Line 2 is longer than 64 bytes and isn't a rotation"
+        );
+    }
+
+    /// Lines longer than it holds at once, that the reference parser still takes
+    #[test]
+    fn test_long_lines() {
+        let input = format!(
+            "L1\nR{}5\n{}\t\r\nL{}\r\nR{}\n",
+            "0".repeat(1000),
+            "\u{3000} ".repeat(100),
+            "0".repeat(100),
+            "0".repeat(64)
+        );
+
+        let rotations = day01::parse_with(&input, true).unwrap();
+        assert_eq!(rotations, [-1, 5, 0, 0]);
+        let expected = (
+            Dial::PUZZLE.count_landings(&rotations),
+            Dial::PUZZLE.count_passes(&rotations),
+        );
+        assert_eq!(
+            solve(input.as_bytes(), Dial::PUZZLE, true).unwrap(),
+            expected
+        );
+
+        // Without skipping blank lines both fail on the blank one
+        assert!(day01::parse(&input).is_err());
+        assert!(solve(input.as_bytes(), Dial::PUZZLE, false).is_err());
+    }
+}
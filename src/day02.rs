@@ -1,16 +1,16 @@
-use color_eyre::eyre::Result;
-use itertools::Itertools;
+use std::ops::RangeInclusive;
 
-use crate::fetch_input;
+use color_eyre::eyre::{Result, eyre};
+use itertools::Itertools;
 
 /// Check if a number is "invalid" - made of some sequence of digits repeated twice
 /// e.g., 55 (5 twice), 6464 (64 twice), 123123 (123 twice)
-fn is_invalid(num: u64) -> bool {
+pub fn is_invalid(num: u64) -> bool {
     let s = num.to_string();
     let len = s.len();
 
     // Must have even number of digits to be repeated twice
-    if len % 2 != 0 {
+    if !len.is_multiple_of(2) {
         return false;
     }
 
@@ -19,33 +19,46 @@ fn is_invalid(num: u64) -> bool {
 }
 
 /// Check if a number is "invalid" - made of some sequence of digits repeated twice or more
-fn is_invalid_2(num: u64) -> bool {
+pub fn is_invalid_2(num: u64) -> bool {
     let s = num.to_string();
     let len = s.len();
 
     for i in 1..len {
         let mut chunks = s.as_bytes().chunks_exact(i);
-        if chunks.remainder().is_empty() {
-            if chunks.all_equal() {
-                return true;
-            }
+        if chunks.remainder().is_empty() && chunks.all_equal() {
+            return true;
         }
     }
 
     false
 }
 
-fn part_1(input: &str) -> Result<u64> {
-    let mut invalid = 0;
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<RangeInclusive<u64>>;
+
+/// Parse comma separated `start-end` ID ranges
+pub fn parse(input: &str) -> Result<Parsed> {
+    input
+        .trim()
+        .split(',')
+        .map(|range_str| {
+            let (start, end) = range_str
+                .trim()
+                .split_once('-')
+                .ok_or_else(|| eyre!("Invalid range {range_str:?}"))?;
+
+            Ok(start.parse()?..=end.parse()?)
+        })
+        .collect()
+}
 
-    // Parse ranges separated by commas
-    for range_str in input.trim().split(',') {
-        let parts: Vec<&str> = range_str.split('-').collect();
-        let start: u64 = parts[0].parse()?;
-        let end: u64 = parts[1].parse()?;
+/// Sum of all IDs in the ranges made of a digit sequence repeated twice
+pub fn part_1(ranges: &[RangeInclusive<u64>]) -> Result<u64> {
+    let mut invalid = 0;
 
+    for range in ranges {
         // Check all IDs in this range (inclusive)
-        for id in start..=end {
+        for id in range.clone() {
             if is_invalid(id) {
                 invalid += id
             }
@@ -55,17 +68,13 @@ fn part_1(input: &str) -> Result<u64> {
     Ok(invalid)
 }
 
-fn part_2(input: &str) -> Result<u64> {
+/// Sum of all IDs in the ranges made of a digit sequence repeated at least twice
+pub fn part_2(ranges: &[RangeInclusive<u64>]) -> Result<u64> {
     let mut invalid = 0;
 
-    // Parse ranges separated by commas
-    for range_str in input.trim().split(',') {
-        let parts: Vec<&str> = range_str.split('-').collect();
-        let start: u64 = parts[0].parse()?;
-        let end: u64 = parts[1].parse()?;
-
+    for range in ranges {
         // Check all IDs in this range (inclusive)
-        for id in start..=end {
+        for id in range.clone() {
             if is_invalid_2(id) {
                 invalid += id
             }
@@ -75,44 +84,75 @@ fn part_2(input: &str) -> Result<u64> {
     Ok(invalid)
 }
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(2)?;
+const SAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+824824821-824824827,2121212118-2121212124
+";
+
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, rngs::StdRng};
+
+    use super::*;
+    use crate::oracle;
+
+    /// A few short ranges of IDs with up to 8 digits
+    fn generate(rng: &mut StdRng) -> String {
+        let ranges = (0..rng.random_range(1..5))
+            .map(|_| {
+                let digits = rng.random_range(1..=8);
+                let start = rng.random_range(10u64.pow(digits - 1)..10u64.pow(digits));
+                let end = start + rng.random_range(0..300);
+                format!("{start}-{end}")
+            })
+            .collect_vec();
+
+        ranges.join(",") + "\n"
+    }
 
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&input)?);
+    /// Numbers of equal chunks `id` splits into, e.g. `[2, 4]` for `1212`
+    fn repeat_counts(id: u64) -> Vec<usize> {
+        let s = id.to_string();
 
-    Ok(())
-}
+        (2..=s.len())
+            .filter(|&n| s.len().is_multiple_of(n) && s[..s.len() / n].repeat(n) == s)
+            .collect()
+    }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     const SAMPLE_INPUT: &str = "L68
-// L30
-// R48
-// L5
-// R60
-// L55
-// L1
-// L99
-// R14
-// L82
-// ";
-
-//     #[test]
-//     fn test_part_1() {
-//         let real_input = fetch_input(1).unwrap();
-
-//         assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 3);
-//         assert_eq!(part_1(&real_input).unwrap(), 1147);
-//     }
-
-//     #[test]
-//     fn test_part_2() {
-//         let real_input = fetch_input(1).unwrap();
-
-//         assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 6);
-//         assert_eq!(part_2(&real_input).unwrap(), 6789);
-//     }
-// }
+    fn brute_force(ranges: &[RangeInclusive<u64>]) -> (u64, u64) {
+        let ids = ranges.iter().cloned().flatten().collect_vec();
+
+        (
+            ids.iter()
+                .filter(|&&id| repeat_counts(id).contains(&2))
+                .sum(),
+            ids.iter()
+                .filter(|&&id| !repeat_counts(id).is_empty())
+                .sum(),
+        )
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 1227775554);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 4174379265);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let ranges = parse(input).unwrap();
+            let (twice, at_least_twice) = brute_force(&ranges);
+
+            assert_eq!(part_1(&ranges).unwrap(), twice);
+            assert_eq!(part_2(&ranges).unwrap(), at_least_twice);
+        });
+    }
+}
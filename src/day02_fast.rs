@@ -0,0 +1,154 @@
+use std::ops::RangeInclusive;
+
+use color_eyre::eyre::{Result, eyre};
+
+use crate::day02;
+pub use crate::day02::{Parsed, parse};
+
+/// Digits in the longest ID that fits
+const MAX_DIGITS: u32 = u64::MAX.ilog10() + 1;
+
+/// What a `block` digit number is multiplied by to repeat it `repeats` times, e.g. 10101 for 3
+/// repeats of 2 digits
+fn repunit(block: u32, repeats: u32) -> u128 {
+    (0..repeats).map(|i| 10u128.pow(block * i)).sum()
+}
+
+/// Sum of the IDs in `range` made of a `block` digit number repeated `repeats` times
+///
+/// They're the blocks times the same [`repunit`], so only the first and last block that land in
+/// the range are needed.
+fn sum_repeated(range: &RangeInclusive<u64>, block: u32, repeats: u32) -> u128 {
+    let multiplier = repunit(block, repeats);
+    let first = u128::from(*range.start())
+        .div_ceil(multiplier)
+        .max(10u128.pow(block - 1));
+    let last = (u128::from(*range.end()) / multiplier).min(10u128.pow(block) - 1);
+
+    if first > last {
+        return 0;
+    }
+    (first + last) * (last - first + 1) / 2 * multiplier
+}
+
+/// Möbius function of `n`: 0 with a repeated prime factor, otherwise -1 to the number of them
+fn mobius(mut n: u32) -> i128 {
+    let mut sign = 1;
+    let mut p = 2;
+
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            sign = -sign;
+        }
+        p += 1;
+    }
+
+    if n > 1 { -sign } else { sign }
+}
+
+fn to_answer(sum: u128) -> Result<u64> {
+    u64::try_from(sum).map_err(|_| eyre!("Sum of invalid IDs {sum} doesn't fit in a u64"))
+}
+
+/// Sum of all IDs in the ranges made of a digit sequence repeated twice
+pub fn part_1(ranges: &[RangeInclusive<u64>]) -> Result<u64> {
+    let sum = ranges
+        .iter()
+        .flat_map(|range| (1..=MAX_DIGITS / 2).map(move |block| sum_repeated(range, block, 2)))
+        .sum();
+
+    to_answer(sum)
+}
+
+/// Sum of all IDs in the ranges made of a digit sequence repeated at least twice
+pub fn part_2(ranges: &[RangeInclusive<u64>]) -> Result<u64> {
+    let mut sum = 0i128;
+
+    for range in ranges {
+        for digits in 2..=MAX_DIGITS {
+            // An ID repeating a block of `b` digits also repeats every multiple of `b` that
+            // divides `digits`, so summing the IDs for each block length counts some of them
+            // several times. Weighting by the Möbius function counts each exactly once.
+            for block in (1..digits).filter(|&block| digits.is_multiple_of(block)) {
+                let repeats = digits / block;
+                let weight = -mobius(repeats);
+                if weight != 0 {
+                    sum += weight * sum_repeated(range, block, repeats) as i128;
+                }
+            }
+        }
+    }
+
+    to_answer(sum as u128)
+}
+
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = day02::SAMPLES;
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, rngs::StdRng};
+
+    use super::*;
+    use crate::oracle;
+
+    /// A few short ranges, some crossing into IDs with one more digit
+    fn generate(rng: &mut StdRng) -> Parsed {
+        (0..rng.random_range(1..5))
+            .map(|_| {
+                let digits = rng.random_range(1..=10);
+                let start = if rng.random_bool(0.3) {
+                    10u64.pow(digits).saturating_sub(rng.random_range(0..300))
+                } else {
+                    rng.random_range(10u64.pow(digits - 1)..10u64.pow(digits))
+                };
+                start..=start + rng.random_range(0..300)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(
+            part_1(&parse(day02::SAMPLES[0].1).unwrap()).unwrap(),
+            1227775554
+        );
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(
+            part_2(&parse(day02::SAMPLES[0].1).unwrap()).unwrap(),
+            4174379265
+        );
+    }
+
+    #[test]
+    fn test_matches_day02() {
+        oracle::check(generate, |ranges| {
+            assert_eq!(part_1(ranges).unwrap(), day02::part_1(ranges).unwrap());
+            assert_eq!(part_2(ranges).unwrap(), day02::part_2(ranges).unwrap());
+        });
+
+        let ranges = [1..=99_999];
+        assert_eq!(part_1(&ranges).unwrap(), day02::part_1(&ranges).unwrap());
+        assert_eq!(part_2(&ranges).unwrap(), day02::part_2(&ranges).unwrap());
+    }
+
+    /// Far too wide to check one ID at a time, but any split into pieces has to add up the same
+    #[test]
+    fn test_wide_ranges() {
+        let whole = [1..=10u64.pow(12)];
+        let pieces = (0..1000)
+            .map(|i| i * 10u64.pow(9) + 1..=(i + 1) * 10u64.pow(9))
+            .collect::<Vec<_>>();
+
+        assert_eq!(part_1(&whole).unwrap(), part_1(&pieces).unwrap());
+        assert_eq!(part_2(&whole).unwrap(), part_2(&pieces).unwrap());
+        assert!(part_2(&[1..=u64::MAX]).is_err());
+    }
+}
@@ -1,23 +1,35 @@
-use std::cmp::minmax;
-
-use color_eyre::eyre::Result;
-use indicatif::ProgressIterator;
+use color_eyre::eyre::{Result, eyre};
 use itertools::Itertools;
 
-use crate::fetch_input;
-
-fn part_1(input: &str) -> Result<u32> {
-    let input = input.trim();
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<Vec<u8>>;
+
+/// Parse each line into a bank of battery joltage digits
+pub fn parse(input: &str) -> Result<Parsed> {
+    input
+        .trim()
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| {
+                    c.to_digit(10)
+                        .map(|d| d as u8)
+                        .ok_or_else(|| eyre!("Invalid battery {c:?} in {line:?}"))
+                })
+                .collect()
+        })
+        .collect()
+}
 
+/// Sum of the largest joltage each bank can produce by turning on two batteries
+pub fn part_1(banks: &[Vec<u8>]) -> Result<u32> {
     let mut sum = 0;
 
-    for line in input.lines() {
-        let numbers = line.chars().map(|c| c.to_digit(10).unwrap()).collect_vec();
-
+    for numbers in banks {
         let mut biggest = 0;
 
         for [a, b] in numbers.iter().array_combinations::<2>() {
-            let n = a * 10 + b;
+            let n = *a as u32 * 10 + *b as u32;
             if n > biggest {
                 biggest = n;
             }
@@ -29,17 +41,11 @@ fn part_1(input: &str) -> Result<u32> {
     Ok(sum)
 }
 
-fn part_2(input: &str) -> Result<u64> {
-    let input = input.trim();
-
+/// Sum of the largest joltage each bank can produce by turning on twelve batteries
+pub fn part_2(banks: &[Vec<u8>]) -> Result<u64> {
     let mut sum = 0;
 
-    for line in input.lines() {
-        let digits = line
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as u64)
-            .collect_vec();
-
+    for digits in banks {
         let n = digits.len();
         let to_pick = 12;
 
@@ -49,70 +55,88 @@ fn part_2(input: &str) -> Result<u64> {
         let mut start = 0; // earliest index we can pick from
 
         for remaining in (1..=to_pick).rev() {
-            let end = n - remaining;
+            let end = n
+                .checked_sub(remaining)
+                .ok_or_else(|| eyre!("Bank has fewer than {to_pick} batteries"))?;
 
-            let (best_idx, best_val) = digits[start..=end]
+            let (best_idx, &best_val) = digits[start..=end]
                 .iter()
                 .enumerate()
                 .max_by(|(i1, v1), (i2, v2)| v1.cmp(v2).then(i2.cmp(i1)))
                 .unwrap();
 
-            result = result * 10 + best_val;
+            result = result * 10 + best_val as u64;
             start = start + best_idx + 1;
         }
 
-        dbg!(result);
-
         sum += result;
     }
 
     Ok(sum)
 }
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(3)?;
+const SAMPLE_INPUT: &str = "987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
+
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, rngs::StdRng};
+
+    use super::*;
+    use crate::oracle;
+
+    /// Banks of 12 to 16 batteries rated 1 to 9
+    fn generate(rng: &mut StdRng) -> String {
+        (0..rng.random_range(1..5))
+            .map(|_| {
+                let bank: String = (0..rng.random_range(12..=16))
+                    .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                    .collect();
+                bank + "\n"
+            })
+            .collect()
+    }
 
-    //     let input = r#"987654321111111
-    // 811111111111119
-    // 234234234234278
-    // 818181911112111
-    // "#;
+    /// Try every choice of `count` batteries in every bank
+    fn brute_force(banks: &[Vec<u8>], count: usize) -> u64 {
+        banks
+            .iter()
+            .map(|bank| {
+                bank.iter()
+                    .combinations(count)
+                    .map(|digits| digits.iter().fold(0, |n, &&d| n * 10 + d as u64))
+                    .max()
+                    .unwrap()
+            })
+            .sum()
+    }
 
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&input)?);
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 357);
+    }
 
-    Ok(())
-}
+    #[test]
+    fn test_part_2() {
+        assert_eq!(
+            part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(),
+            3121910778619
+        );
+    }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     const SAMPLE_INPUT: &str = "L68
-// L30
-// R48
-// L5
-// R60
-// L55
-// L1
-// L99
-// R14
-// L82
-// ";
-
-//     #[test]
-//     fn test_part_1() {
-//         let real_input = fetch_input(1).unwrap();
-
-//         assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 3);
-//         assert_eq!(part_1(&real_input).unwrap(), 1147);
-//     }
-
-//     #[test]
-//     fn test_part_2() {
-//         let real_input = fetch_input(1).unwrap();
-
-//         assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 6);
-//         assert_eq!(part_2(&real_input).unwrap(), 6789);
-//     }
-// }
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let banks = parse(input).unwrap();
+
+            assert_eq!(part_1(&banks).unwrap() as u64, brute_force(&banks, 2));
+            assert_eq!(part_2(&banks).unwrap(), brute_force(&banks, 12));
+        });
+    }
+}
@@ -1,7 +1,5 @@
 use color_eyre::eyre::Result;
 
-use crate::fetch_input;
-
 const DIRECTIONS: [(isize, isize); 8] = [
     (-1, -1),
     (-1, 0),
@@ -13,21 +11,24 @@ const DIRECTIONS: [(isize, isize); 8] = [
     (1, 1),
 ];
 
-struct Grid {
-    cells: Vec<Vec<char>>,
+/// Grid of paper rolls (`@`) and empty floor (`.`)
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub cells: Vec<Vec<char>>,
 }
 
 impl Grid {
-    fn new(input: &str) -> Self {
+    pub fn new(input: &str) -> Self {
         let cells = input.trim().lines().map(|l| l.chars().collect()).collect();
         Self { cells }
     }
 
-    fn get(&self, y: usize, x: usize) -> Option<char> {
+    pub fn get(&self, y: usize, x: usize) -> Option<char> {
         self.cells.get(y).and_then(|row| row.get(x).copied())
     }
 
-    fn count_neighbors(&self, y: usize, x: usize, target: char) -> usize {
+    /// Count the 8 surrounding cells equal to `target`
+    pub fn count_neighbors(&self, y: usize, x: usize, target: char) -> usize {
         DIRECTIONS
             .iter()
             .filter(|&&(dy, dx)| {
@@ -40,21 +41,28 @@ impl Grid {
             .count()
     }
 
-    fn iter_positions(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+    /// Iterate over `(y, x, cell)` in row-major order
+    pub fn iter_positions(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
         self.cells
             .iter()
             .enumerate()
             .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, &c)| (y, x, c)))
     }
 
-    fn set(&mut self, y: usize, x: usize, c: char) {
+    pub fn set(&mut self, y: usize, x: usize, c: char) {
         self.cells[y][x] = c;
     }
 }
 
-fn part_1(input: &str) -> Result<usize> {
-    let grid = Grid::new(input);
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Grid;
+
+pub fn parse(input: &str) -> Result<Parsed> {
+    Ok(Grid::new(input))
+}
 
+/// Count the rolls with fewer than four neighbouring rolls
+pub fn part_1(grid: &Grid) -> Result<usize> {
     let forklifts = grid
         .iter_positions()
         .filter(|&(y, x, c)| c == '@' && grid.count_neighbors(y, x, '@') < 4)
@@ -63,8 +71,9 @@ fn part_1(input: &str) -> Result<usize> {
     Ok(forklifts)
 }
 
-fn part_2(input: &str) -> Result<usize> {
-    let mut grid = Grid::new(input);
+/// Count the rolls that can be removed by repeatedly removing accessible rolls
+pub fn part_2(grid: &Grid) -> Result<usize> {
+    let mut grid = grid.clone();
     let mut forklifts = 0;
 
     loop {
@@ -87,20 +96,7 @@ fn part_2(input: &str) -> Result<usize> {
     Ok(forklifts)
 }
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(4)?;
-
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&input)?);
-
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE_INPUT: &str = "..@@.@@@@.
+const SAMPLE_INPUT: &str = "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
 @.@@@@..@.
@@ -112,19 +108,89 @@ mod tests {
 @.@.@@@.@.
 ";
 
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, rngs::StdRng};
+
+    use super::*;
+    use crate::{fetch_input, oracle};
+
+    /// Small grids of randomly placed rolls
+    fn generate(rng: &mut StdRng) -> String {
+        let (height, width) = (rng.random_range(1..12), rng.random_range(1..12));
+        let density = rng.random_range(0.2..0.9);
+
+        (0..height)
+            .map(|_| {
+                let row: String = (0..width)
+                    .map(|_| if rng.random_bool(density) { '@' } else { '.' })
+                    .collect();
+                row + "\n"
+            })
+            .collect()
+    }
+
+    fn accessible(rolls: &[Vec<bool>], y: usize, x: usize) -> bool {
+        let neighbors = (y.saturating_sub(1)..=y + 1)
+            .flat_map(|ny| (x.saturating_sub(1)..=x + 1).map(move |nx| (ny, nx)))
+            .filter(|&(ny, nx)| (ny, nx) != (y, x))
+            .filter(|&(ny, nx)| rolls.get(ny).and_then(|row| row.get(nx)) == Some(&true))
+            .count();
+
+        rolls[y][x] && neighbors < 4
+    }
+
+    /// Count accessible rolls, then remove them one at a time until none are left
+    fn brute_force(input: &str) -> (usize, usize) {
+        let mut rolls = input
+            .lines()
+            .map(|line| line.chars().map(|c| c == '@').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let positions = (0..rolls.len())
+            .flat_map(|y| (0..rolls[y].len()).map(move |x| (y, x)))
+            .collect::<Vec<_>>();
+
+        let initially = positions
+            .iter()
+            .filter(|&&(y, x)| accessible(&rolls, y, x))
+            .count();
+
+        let mut removed = 0;
+        while let Some(&(y, x)) = positions.iter().find(|&&(y, x)| accessible(&rolls, y, x)) {
+            rolls[y][x] = false;
+            removed += 1;
+        }
+
+        (initially, removed)
+    }
+
     #[test]
     fn test_part_1() {
         let real_input = fetch_input(4).unwrap();
 
-        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 13);
-        assert_eq!(part_1(&real_input).unwrap(), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 13);
+        assert_eq!(part_1(&parse(&real_input).unwrap()).unwrap(), 1533);
     }
 
     #[test]
     fn test_part_2() {
         let real_input = fetch_input(4).unwrap();
 
-        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 43);
-        assert_eq!(part_2(&real_input).unwrap(), 9206);
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 43);
+        assert_eq!(part_2(&parse(&real_input).unwrap()).unwrap(), 9206);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let (accessible, removed) = brute_force(input);
+            let grid = parse(input).unwrap();
+
+            assert_eq!(part_1(&grid).unwrap(), accessible);
+            assert_eq!(part_2(&grid).unwrap(), removed);
+        });
     }
 }
@@ -1,59 +1,62 @@
 use std::ops::RangeInclusive;
 
-use color_eyre::eyre::Result;
-use itertools::Itertools;
+use color_eyre::eyre::{Result, eyre};
 use rangemap::RangeInclusiveSet;
 
-use crate::fetch_input;
+/// The ingredient database
+pub struct Inventory {
+    /// Ranges of fresh ingredient IDs, possibly overlapping
+    pub fresh: Vec<RangeInclusive<usize>>,
+    /// IDs of the available ingredients
+    pub available: Vec<usize>,
+}
+
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Inventory;
 
-fn part_1(input: &str) -> Result<usize> {
+/// Parse the fresh ranges and available IDs, separated by a blank line
+pub fn parse(input: &str) -> Result<Parsed> {
     let input = input.trim();
 
-    let (fresh_ingredient_ranges, available_ingredients) = input.split_once("\n\n").unwrap();
+    let (fresh_ingredient_ranges, available_ingredients) = input
+        .split_once("\n\n")
+        .ok_or_else(|| eyre!("Missing blank line between ranges and IDs"))?;
 
-    let fresh_ingredient_ranges = fresh_ingredient_ranges
+    let fresh = fresh_ingredient_ranges
         .lines()
         .map(|l| {
-            let parts = l.split_once('-').unwrap();
-            let (start, last) = (
-                parts.0.parse::<usize>().unwrap(),
-                parts.1.parse::<usize>().unwrap(),
-            );
-            std::range::RangeInclusive { start, last }
+            let (start, last) = l
+                .split_once('-')
+                .ok_or_else(|| eyre!("Invalid range {l:?}"))?;
+
+            Ok(start.parse()?..=last.parse()?)
         })
-        .collect_vec();
+        .collect::<Result<_>>()?;
 
-    let fresh_ids = available_ingredients
+    let available = available_ingredients
         .lines()
-        .map(|l| l.parse::<usize>().unwrap())
-        .filter(|&id| {
-            fresh_ingredient_ranges
-                .iter()
-                .any(|range| range.contains(&id))
-        })
-        .count();
+        .map(|l| Ok(l.parse()?))
+        .collect::<Result<_>>()?;
 
-    Ok(fresh_ids)
+    Ok(Inventory { fresh, available })
 }
 
-fn part_2(input: &str) -> Result<usize> {
-    let input = input.trim();
-
-    let (fresh_ingredient_ranges, available_ingredients) = input.split_once("\n\n").unwrap();
-
-    let mut range_set = RangeInclusiveSet::new();
+/// Count the available ingredients that fall within any fresh range
+pub fn part_1(inventory: &Inventory) -> Result<usize> {
+    let fresh_ids = inventory
+        .available
+        .iter()
+        .filter(|id| inventory.fresh.iter().any(|range| range.contains(id)))
+        .count();
 
-    fresh_ingredient_ranges.lines().for_each(|l| {
-        let parts = l.split_once('-').unwrap();
-        let (start, last) = (
-            parts.0.parse::<usize>().unwrap(),
-            parts.1.parse::<usize>().unwrap(),
-        );
+    Ok(fresh_ids)
+}
 
-        range_set.insert(start..=last);
-    });
+/// Count every ID considered fresh by the ranges
+pub fn part_2(inventory: &Inventory) -> Result<usize> {
+    let range_set: RangeInclusiveSet<usize> = inventory.fresh.iter().cloned().collect();
 
-    Ok(range_set.into_iter().flatten().count())
+    Ok(range_set.iter().map(|r| r.end() - r.start() + 1).sum())
 }
 
 const SAMPLE_INPUT: &str = "3-5
@@ -69,34 +72,69 @@ const SAMPLE_INPUT: &str = "3-5
 32
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(5)?;
-
-    dbg!(part_1(&SAMPLE_INPUT)?);
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&SAMPLE_INPUT)?);
-    dbg!(part_2(&input)?);
-
-    Ok(())
-}
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
+    use crate::oracle;
+
+    /// Overlapping and touching ranges of small IDs, and IDs both in and out of them
+    fn generate(rng: &mut StdRng) -> String {
+        let mut input = String::new();
+
+        for _ in 0..rng.random_range(1..8) {
+            let start = rng.random_range(0..200);
+            let last = start + rng.random_range(0..30);
+            input += &format!("{start}-{last}\n");
+        }
+
+        input += "\n";
+
+        for _ in 0..rng.random_range(1..10) {
+            input += &format!("{}\n", rng.random_range(0..240));
+        }
+
+        input
+    }
+
+    /// Mark every fresh ID one by one
+    fn brute_force(inventory: &Inventory) -> (usize, usize) {
+        let mut fresh = vec![false; 256];
+
+        for range in &inventory.fresh {
+            for id in range.clone() {
+                fresh[id] = true;
+            }
+        }
+
+        (
+            inventory.available.iter().filter(|&&id| fresh[id]).count(),
+            fresh.iter().filter(|&&f| f).count(),
+        )
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(4).unwrap();
-
-        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 13);
-        assert_eq!(part_1(&real_input).unwrap(), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 3);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(4).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 14);
+    }
 
-        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 43);
-        assert_eq!(part_2(&real_input).unwrap(), 9206);
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let inventory = parse(input).unwrap();
+            let (available, total) = brute_force(&inventory);
+
+            assert_eq!(part_1(&inventory).unwrap(), available);
+            assert_eq!(part_2(&inventory).unwrap(), total);
+        });
     }
 }
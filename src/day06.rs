@@ -1,128 +1,159 @@
-use core::str;
-use std::ops::RangeInclusive;
-
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{Result, bail, eyre};
 use itertools::Itertools;
-use rangemap::RangeInclusiveSet;
-
-use crate::fetch_input;
-
-fn part_1(input: &str) -> Result<usize> {
-    let input = input.trim();
 
-    let mut lines = input.lines().map(|l| l.to_owned()).collect_vec();
-    let operators = lines.pop().unwrap();
-    let operators = operators.split_whitespace().collect_vec();
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Multiply,
+}
 
-    let numbers = lines
-        .into_iter()
-        .map(|l| {
-            l.split_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect_vec()
-        })
-        .collect_vec();
+impl TryFrom<u8> for Operator {
+    type Error = color_eyre::Report;
 
-    let mut total_sum = 0;
+    fn try_from(c: u8) -> Result<Self> {
+        match c {
+            b'+' => Ok(Self::Add),
+            b'*' => Ok(Self::Multiply),
+            _ => bail!("Invalid operator {:?}", c as char),
+        }
+    }
+}
 
-    for (i, op) in operators.iter().enumerate() {
-        let mut sum = match *op {
-            "+" => 0,
-            "*" => 1,
-            _ => unreachable!(),
-        };
+/// One problem on the worksheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub operator: Operator,
+    pub numbers: Vec<usize>,
+}
 
-        for line in &numbers {
-            match *op {
-                "+" => sum += line[i],
-                "*" => sum *= line[i],
-                _ => unreachable!(),
-            }
+impl Problem {
+    pub fn solve(&self) -> usize {
+        match self.operator {
+            Operator::Add => self.numbers.iter().sum(),
+            Operator::Multiply => self.numbers.iter().product(),
         }
-
-        total_sum += sum;
     }
-
-    Ok(total_sum)
 }
 
-fn part_2(input: &str) -> Result<usize> {
-    let input = input.trim();
+/// Split the worksheet into number rows and the operator row
+fn split_lines(input: &str) -> Result<(Vec<&[u8]>, &[u8])> {
+    let mut lines = input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.as_bytes())
+        .collect_vec();
+
+    let operators = lines.pop().ok_or_else(|| eyre!("Empty worksheet"))?;
 
-    let mut lines = input.lines().map(|l| l.as_bytes()).collect_vec();
+    Ok((lines, operators))
+}
 
-    let operators = lines.pop().unwrap();
-    let len = operators.len();
+/// Parse problems the human way, reading numbers left to right along each row
+pub fn parse_rows(input: &str) -> Result<Vec<Problem>> {
+    let (lines, operators) = split_lines(input)?;
 
-    let mut operators = operators
+    let numbers = lines
         .into_iter()
-        .copied()
-        .enumerate()
-        .filter(|(i, c)| *c != b' ')
-        .collect_vec();
+        .map(|l| {
+            str::from_utf8(l)?
+                .split_whitespace()
+                .map(|n| Ok(n.parse::<usize>()?))
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-    operators.push((len + 2, b's'));
+    operators
+        .iter()
+        .filter(|c| !c.is_ascii_whitespace())
+        .enumerate()
+        .map(|(i, &op)| {
+            let numbers = numbers
+                .iter()
+                .map(|line| {
+                    line.get(i)
+                        .copied()
+                        .ok_or_else(|| eyre!("Row is missing a number for problem {i}"))
+                })
+                .collect::<Result<_>>()?;
+
+            Ok(Problem {
+                operator: Operator::try_from(op)?,
+                numbers,
+            })
+        })
+        .collect()
+}
 
-    dbg!(&operators);
+/// Parse problems the cephalopod way, reading each column top to bottom as one number
+pub fn parse_columns(input: &str) -> Result<Vec<Problem>> {
+    let (lines, operators) = split_lines(input)?;
 
-    let mut total_sum = 0;
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
 
-    for [op, next_op] in operators.array_windows::<2>() {
-        let &(i, op) = op;
-        if op == b's' {
-            continue;
-        }
+    // Each problem starts at its operator and ends at the blank column before the next one
+    let starts = operators
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_ascii_whitespace())
+        .map(|(i, _)| i)
+        .collect_vec();
 
-        let start = i;
-        let end = next_op.0 - 1;
-
-        dbg!(str::from_utf8(&lines[0][start..end]));
-
-        let lines = lines
-            .iter()
-            .map(|l| {
-                l[start..end]
-                    .iter()
-                    .copied()
-                    .map(|c| match c {
-                        b' ' => None,
-                        c => Some((c as char).to_digit(10).unwrap() as usize),
-                    })
-                    .collect_vec()
+    let ends = starts
+        .iter()
+        .skip(1)
+        .map(|&next| next - 1)
+        .chain([width.max(operators.len())]);
+
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| {
+            let numbers = (start..end)
+                .filter_map(|x| {
+                    let digits = lines
+                        .iter()
+                        .filter_map(|l| l.get(x))
+                        .filter(|c| c.is_ascii_digit())
+                        .map(|c| (c - b'0') as usize)
+                        .collect_vec();
+
+                    (!digits.is_empty()).then(|| digits.iter().fold(0, |n, d| n * 10 + d))
+                })
+                .collect();
+
+            Ok(Problem {
+                operator: Operator::try_from(operators[start])?,
+                numbers,
             })
-            .collect_vec();
-        dbg!(&lines);
-
-        let mut sum = match op {
-            b'+' => 0,
-            b'*' => 1,
-            _ => unreachable!(),
-        };
+        })
+        .collect()
+}
 
-        let numbers = lines[0].len();
-        for x in 0..numbers {
-            let mut number = 0;
+/// The worksheet's problems, read both ways
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet {
+    pub rows: Vec<Problem>,
+    pub columns: Vec<Problem>,
+}
 
-            for line in &lines {
-                let digit = line[x];
-                if digit.is_none() {
-                    continue;
-                }
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Worksheet;
 
-                number *= 10;
-                number += digit.unwrap();
-            }
+pub fn parse(input: &str) -> Result<Parsed> {
+    Ok(Worksheet {
+        rows: parse_rows(input)?,
+        columns: parse_columns(input)?,
+    })
+}
 
-            match op {
-                b'+' => sum += number,
-                b'*' => sum *= number,
-                _ => unreachable!(),
-            }
-        }
-        total_sum += sum;
-    }
+/// Grand total of the worksheet read row by row
+pub fn part_1(worksheet: &Worksheet) -> Result<usize> {
+    Ok(worksheet.rows.iter().map(Problem::solve).sum())
+}
 
-    Ok(total_sum)
+/// Grand total of the worksheet read column by column
+pub fn part_2(worksheet: &Worksheet) -> Result<usize> {
+    Ok(worksheet.columns.iter().map(Problem::solve).sum())
 }
 
 const SAMPLE_INPUT: &str = "123 328  51 64
@@ -131,34 +162,121 @@ const SAMPLE_INPUT: &str = "123 328  51 64
 *   +   *   +
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(6)?;
-
-    // dbg!(part_1(&SAMPLE_INPUT)?);
-    // dbg!(part_1(&input)?);
-    dbg!(part_2(&SAMPLE_INPUT)?);
-    dbg!(part_2(&input)?);
-
-    Ok(())
-}
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
+    use crate::oracle;
+
+    /// A worksheet of problems, each with its numbers aligned left or right within its columns
+    fn generate(rng: &mut StdRng) -> String {
+        let rows = rng.random_range(1..=4);
+        let mut lines = vec![String::new(); rows + 1];
+
+        for problem in 0..rng.random_range(1..6) {
+            // Worksheets don't have zero digits
+            let numbers = (0..rows)
+                .map(|_| {
+                    let len = rng.random_range(1..=4);
+                    (0..len)
+                        .map(|_| char::from(b'0' + rng.random_range(1..=9)))
+                        .collect::<String>()
+                })
+                .collect_vec();
+            let width = numbers.iter().map(String::len).max().unwrap();
+            let left_aligned = rng.random_bool(0.5);
+
+            for (line, number) in lines.iter_mut().zip(&numbers) {
+                if problem > 0 {
+                    line.push(' ');
+                }
+                match left_aligned {
+                    true => line.push_str(&format!("{number:<width$}")),
+                    false => line.push_str(&format!("{number:>width$}")),
+                }
+            }
+
+            let operator = if rng.random_bool(0.5) { '+' } else { '*' };
+            let operators = lines.last_mut().unwrap();
+            if problem > 0 {
+                operators.push(' ');
+            }
+            operators.push_str(&format!("{operator:<width$}"));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Split the worksheet at blank columns and read each block both ways
+    fn brute_force(input: &str) -> (usize, usize) {
+        let grid = input.lines().map(|l| l.as_bytes()).collect_vec();
+        let (numbers, operators) = grid.split_at(grid.len() - 1);
+        let width = grid.iter().map(|l| l.len()).max().unwrap();
+        let at = |line: &[u8], x: usize| line.get(x).copied().unwrap_or(b' ');
+
+        let blank = |x: usize| grid.iter().all(|line| at(line, x) == b' ');
+        let mut blocks = vec![];
+        let mut start = 0;
+        for x in 0..=width {
+            if x == width || blank(x) {
+                blocks.push(start..x);
+                start = x + 1;
+            }
+        }
+
+        let solve = |operator: u8, numbers: Vec<usize>| match operator {
+            b'+' => numbers.iter().sum::<usize>(),
+            _ => numbers.iter().product(),
+        };
+
+        let mut totals = (0, 0);
+        for block in blocks {
+            let operator = at(operators[0], block.start);
+
+            let rows = numbers
+                .iter()
+                .map(|line| {
+                    let text: String = block.clone().map(|x| at(line, x) as char).collect();
+                    text.trim().parse().unwrap()
+                })
+                .collect();
+            totals.0 += solve(operator, rows);
+
+            let columns = block
+                .map(|x| {
+                    // Shorter numbers leave gaps in the column
+                    let text: String = numbers.iter().map(|line| at(line, x) as char).collect();
+                    text.replace(' ', "").parse().unwrap()
+                })
+                .collect();
+            totals.1 += solve(operator, columns);
+        }
+
+        totals
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(4).unwrap();
-
-        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 13);
-        assert_eq!(part_1(&real_input).unwrap(), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 4277556);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(4).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 3263827);
+    }
 
-        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 43);
-        assert_eq!(part_2(&real_input).unwrap(), 9206);
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let (rows, columns) = brute_force(input);
+            let worksheet = parse(input).unwrap();
+
+            assert_eq!(part_1(&worksheet).unwrap(), rows);
+            assert_eq!(part_2(&worksheet).unwrap(), columns);
+        });
     }
 }
@@ -1,42 +1,44 @@
-use core::str;
-use std::{
-    collections::{HashMap, HashSet},
-    io::BufRead,
-    ops::RangeInclusive,
-};
+use std::collections::{HashMap, HashSet};
 
-use color_eyre::eyre::Result;
-use itertools::Itertools;
-use rangemap::RangeInclusiveSet;
+use color_eyre::eyre::{Result, eyre};
 
-use crate::fetch_input;
+/// The tachyon manifold diagram
+pub struct Manifold {
+    /// Column of the beam entry point `S` on the first row
+    pub start: usize,
+    /// Every row below the first, `true` where there is a splitter `^`
+    pub splitters: Vec<Vec<bool>>,
+}
 
-fn part_1(input: &str) -> Result<usize> {
-    let input = input.trim();
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Manifold;
 
-    let mut grid = input.lines().map(|line| line.as_bytes()).collect_vec();
+/// Parse the diagram, locating the start on the first row
+pub fn parse(input: &str) -> Result<Parsed> {
+    let mut lines = input.trim().lines();
 
-    let mut start = None;
+    let start = lines
+        .next()
+        .and_then(|first| first.bytes().position(|c| c == b'S'))
+        .ok_or_else(|| eyre!("Missing start position S on the first row"))?;
 
-    for (x, &cell) in grid[0].iter().enumerate() {
-        if cell == b'S' {
-            start = Some(x);
-        }
-    }
+    let splitters = lines
+        .map(|line| line.bytes().map(|c| c == b'^').collect())
+        .collect();
 
-    dbg!(start);
+    Ok(Manifold { start, splitters })
+}
 
-    let start_x = start.unwrap();
+/// Count how many times the beam is split
+pub fn part_1(manifold: &Manifold) -> Result<usize> {
     let mut tachyon_beams = HashSet::new();
-    tachyon_beams.insert(start_x);
+    tachyon_beams.insert(manifold.start);
 
     let mut splits = 0;
 
-    for y in 1..grid.len() {
-        let row = &grid[y];
-
+    for row in &manifold.splitters {
         for beam in tachyon_beams.clone() {
-            if row[beam] == b'^' {
+            if row[beam] {
                 splits += 1;
                 tachyon_beams.remove(&beam);
                 tachyon_beams.insert(beam - 1);
@@ -45,36 +47,19 @@ fn part_1(input: &str) -> Result<usize> {
         }
     }
 
-    dbg!(tachyon_beams.len());
-
     Ok(splits)
 }
 
-fn part_2(input: &str) -> Result<usize> {
-    let input = input.trim();
-
-    let mut grid = input.lines().map(|line| line.as_bytes()).collect_vec();
-
-    let mut start = None;
-
-    for (x, &cell) in grid[0].iter().enumerate() {
-        if cell == b'S' {
-            start = Some(x);
-        }
-    }
-
-    dbg!(start);
-
-    let start_x = start.unwrap();
+/// Count the timelines a single particle ends up in
+pub fn part_2(manifold: &Manifold) -> Result<usize> {
     let mut timelines = HashMap::new();
-    timelines.insert(start_x, 1);
+    timelines.insert(manifold.start, 1);
 
-    for y in 1..grid.len() {
-        let row = &grid[y];
+    for row in &manifold.splitters {
         let mut new_timelines: HashMap<usize, usize> = HashMap::new();
 
         for (&pos, &count) in &timelines {
-            if row[pos] == b'^' {
+            if row[pos] {
                 *new_timelines.entry(pos - 1).or_default() += count;
                 *new_timelines.entry(pos + 1).or_default() += count;
             } else {
@@ -85,7 +70,6 @@ fn part_2(input: &str) -> Result<usize> {
         timelines = new_timelines;
     }
 
-    dbg!(timelines.len());
     Ok(timelines.values().sum())
 }
 
@@ -107,34 +91,101 @@ const SAMPLE_INPUT: &str = ".......S.......
 ...............
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(7)?;
-
-    dbg!(part_1(&SAMPLE_INPUT)?);
-    dbg!(part_1(&input)?);
-    dbg!(part_2(&SAMPLE_INPUT)?);
-    dbg!(part_2(&input)?);
-
-    Ok(())
-}
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
+    use crate::oracle;
+
+    /// A diagram like the real ones, with splitters on every other row spaced so beams hit them
+    fn generate(rng: &mut StdRng) -> String {
+        let width = rng.random_range(2..8) * 2 + 1;
+        let start = width / 2;
+        let density = rng.random_range(0.3..1.0);
+
+        let mut lines = vec![format!("{:.<width$}", format!("{:.<start$}S", ""))];
+
+        for level in 0..rng.random_range(0..7) {
+            let splitters: String = (0..width)
+                .map(|x| {
+                    let reachable = x.abs_diff(start) <= level && (x + start + level) % 2 == 0;
+                    let inside = x > 0 && x < width - 1;
+
+                    match reachable && inside && rng.random_bool(density) {
+                        true => '^',
+                        false => '.',
+                    }
+                })
+                .collect();
+
+            lines.push(splitters);
+            lines.push(".".repeat(width));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Number of splitters hit by the beams, simulated one row at a time
+    fn brute_force_splits(manifold: &Manifold) -> usize {
+        let width = manifold.splitters.first().map_or(0, Vec::len);
+        let mut beams = vec![false; width.max(manifold.start + 1)];
+        beams[manifold.start] = true;
+        let mut splits = 0;
+
+        for row in &manifold.splitters {
+            let mut next = vec![false; beams.len()];
+
+            for x in (0..beams.len()).filter(|&x| beams[x]) {
+                if row[x] {
+                    splits += 1;
+                    next[x - 1] = true;
+                    next[x + 1] = true;
+                } else {
+                    next[x] = true;
+                }
+            }
+
+            beams = next;
+        }
+
+        splits
+    }
+
+    /// Follow every timeline separately
+    fn brute_force_timelines(splitters: &[Vec<bool>], x: usize) -> usize {
+        match splitters.split_first() {
+            None => 1,
+            Some((row, rest)) if row[x] => {
+                brute_force_timelines(rest, x - 1) + brute_force_timelines(rest, x + 1)
+            }
+            Some((_, rest)) => brute_force_timelines(rest, x),
+        }
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(4).unwrap();
-
-        assert_eq!(part_1(SAMPLE_INPUT).unwrap(), 13);
-        assert_eq!(part_1(&real_input).unwrap(), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 21);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(4).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 40);
+    }
 
-        assert_eq!(part_2(SAMPLE_INPUT).unwrap(), 43);
-        assert_eq!(part_2(&real_input).unwrap(), 9206);
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let manifold = parse(input).unwrap();
+
+            assert_eq!(part_1(&manifold).unwrap(), brute_force_splits(&manifold));
+            assert_eq!(
+                part_2(&manifold).unwrap(),
+                brute_force_timelines(&manifold.splitters, manifold.start)
+            );
+        });
     }
 }
@@ -1,39 +1,33 @@
-use core::str;
-use std::{
-    cmp::Reverse,
-    collections::{HashMap, HashSet},
-    io::BufRead,
-    ops::RangeInclusive,
-};
+use std::cmp::Reverse;
 
 use color_eyre::eyre::Result;
 use itertools::Itertools;
-use ordered_float::OrderedFloat;
-use vek::Vec3;
 
-use crate::fetch_input;
+use crate::{geometry::Point3, input::Input};
 
-struct UnionFind {
+/// Disjoint sets of junction boxes, i.e. circuits
+pub struct UnionFind {
     parent: Vec<usize>,
-    size: Vec<usize>,
+    /// Size of the set rooted at each index
+    pub size: Vec<usize>,
 }
 
 impl UnionFind {
-    fn new(n: usize) -> Self {
+    pub fn new(n: usize) -> Self {
         Self {
             parent: (0..n).collect(), // each element is its own parent
             size: vec![1; n],         // each set starts with size 1
         }
     }
 
-    fn find(&mut self, x: usize) -> usize {
+    pub fn find(&mut self, x: usize) -> usize {
         if self.parent[x] != x {
             self.parent[x] = self.find(self.parent[x]); // path compression
         }
         self.parent[x]
     }
 
-    fn union(&mut self, x: usize, y: usize) {
+    pub fn union(&mut self, x: usize, y: usize) {
         let px = self.find(x);
         let py = self.find(y);
         if px != py {
@@ -49,55 +43,42 @@ impl UnionFind {
     }
 }
 
-fn part_1<const N: usize>(input: &str) -> usize {
-    let input = input.trim();
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<Point3>;
 
-    let points = input
-        .lines()
-        .map(|line| {
-            let (x, y, z) = line
-                .split(',')
-                .map(|s| s.parse::<i64>().unwrap())
-                .collect_tuple()
-                .unwrap();
+/// Parse one `x,y,z` junction box position per line
+pub fn parse(input: &str) -> Result<Parsed> {
+    input.trim().lines().map(str::parse).collect()
+}
 
-            Vec3::new(x, y, z)
-        })
-        .collect_vec();
+/// Connect the `connections` closest pairs and multiply the sizes of the three largest circuits
+pub fn part_1(points: &[Point3], connections: usize) -> Result<usize> {
     let n = points.len();
 
     let pairs = (0..n)
         .array_combinations::<2>()
-        .sorted_unstable_by_key(|&[a, b]| (points[a].distance_squared(points[b])))
+        .sorted_unstable_by_key(|&[a, b]| points[a].distance_squared(points[b]))
         .collect_vec();
 
     let mut union = UnionFind::new(n);
-    for &[a, b] in &pairs[0..N] {
+    for &[a, b] in pairs.iter().take(connections) {
         union.union(a, b);
     }
 
-    union.size.sort_unstable_by_key(|&n| Reverse(n));
+    // Sizes are only kept up to date for the root of each circuit
+    let sizes = (0..n)
+        .filter(|&i| union.parent[i] == i)
+        .map(|i| union.size[i])
+        .sorted_unstable_by_key(|&n| Reverse(n));
 
-    let largest_groups = union.size.into_iter().take(3).product();
+    let largest_groups = sizes.take(3).product();
 
-    largest_groups
+    Ok(largest_groups)
 }
 
-fn part_2(input: &str) -> usize {
-    let input = input.trim();
-
-    let points = input
-        .lines()
-        .map(|line| {
-            let (x, y, z) = line
-                .split(',')
-                .map(|s| s.parse::<i64>().unwrap())
-                .collect_tuple()
-                .unwrap();
-
-            Vec3::new(x, y, z)
-        })
-        .collect_vec();
+/// Connect closest pairs until everything is one circuit, then multiply the X coordinates of
+/// the last pair connected
+pub fn part_2(points: &[Point3]) -> Result<usize> {
     let n = points.len();
 
     let pairs = (0..n)
@@ -119,12 +100,12 @@ fn part_2(input: &str) -> usize {
             if num_components == 1 {
                 // All nodes are now in one component
                 // Return the product of X coordinates
-                return (points[a].x as usize) * (points[b].x as usize);
+                return Ok((points[a].x as usize) * (points[b].x as usize));
             }
         }
     }
 
-    0
+    Ok(0)
 }
 
 const SAMPLE_INPUT: &str = "162,817,812
@@ -149,34 +130,128 @@ const SAMPLE_INPUT: &str = "162,817,812
 425,690,689
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(8)?;
-
-    dbg!(part_1::<10>(&SAMPLE_INPUT));
-    dbg!(part_1::<1000>(&input));
-    dbg!(part_2(&SAMPLE_INPUT));
-    dbg!(part_2(&input));
-
-    Ok(())
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
+
+/// Boxes in the example, which connects fewer pairs than the real input
+const SAMPLE_BOXES: usize = 20;
+
+/// Number of pairs to connect in part 1, overridable with the `connections` parameter
+///
+/// The example's 20 boxes connect 10 pairs and the real input's 1000 boxes connect 1000. Without
+/// the parameter that's decided by the number of boxes, so the same text gets the same answer
+/// wherever it came from.
+pub fn connections(points: &[Point3], input: &Input) -> Result<usize> {
+    let default_connections = if points.len() <= SAMPLE_BOXES {
+        10
+    } else {
+        1000
+    };
+    Ok(input.param("connections")?.unwrap_or(default_connections))
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
+    use crate::{input::InputSource, oracle};
+
+    /// Junction boxes with no two pairs equally far apart, so the connection order is unique
+    fn generate(rng: &mut StdRng) -> (String, usize) {
+        loop {
+            let n = rng.random_range(2..25);
+            let points = (0..n)
+                .map(|_| {
+                    let [x, y, z] = [0; 3].map(|_| rng.random_range(0..1000));
+                    Point3::new(x, y, z)
+                })
+                .collect_vec();
+
+            let distances = points
+                .iter()
+                .array_combinations::<2>()
+                .map(|[a, b]| a.distance_squared(*b))
+                .collect_vec();
+            if !distances.iter().all_unique() {
+                continue;
+            }
+
+            let input = points.iter().map(|p| format!("{p}\n")).collect();
+            return (input, rng.random_range(1..=distances.len()));
+        }
+    }
+
+    /// Label every box with its circuit, relabelling a whole circuit on each connection
+    fn brute_force(points: &[Point3], connections: usize) -> (usize, usize) {
+        let mut circuit = (0..points.len()).collect_vec();
+
+        let pairs = (0..points.len())
+            .array_combinations::<2>()
+            .sorted_by_key(|&[a, b]| points[a].distance_squared(points[b]))
+            .collect_vec();
+
+        let mut largest = 0;
+        let mut last_x = 0;
+
+        for (i, &[a, b]) in pairs.iter().enumerate() {
+            let (from, to) = (circuit[b], circuit[a]);
+            if from != to {
+                circuit
+                    .iter_mut()
+                    .filter(|c| **c == from)
+                    .for_each(|c| *c = to);
+                last_x = points[a].x as usize * points[b].x as usize;
+            }
+
+            if i + 1 == connections {
+                let sizes = circuit.iter().counts().into_values().sorted().rev();
+                largest = sizes.take(3).product();
+            }
+        }
+
+        (largest, last_x)
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(4).unwrap();
-
-        assert_eq!(part_1::<10>(SAMPLE_INPUT), 13);
-        assert_eq!(part_1::<1000>(&real_input), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap(), 10).unwrap(), 40);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(4).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 25272);
+    }
+
+    #[test]
+    fn test_connections() {
+        let sample = parse(SAMPLE_INPUT).unwrap();
+        let real = (0..1000).map(|i| Point3::new(i, 0, 0)).collect_vec();
+
+        for source in [
+            InputSource::Sample(None),
+            InputSource::Stdin,
+            InputSource::Fetch,
+        ] {
+            assert_eq!(
+                connections(&sample, &Input::new(source.clone())).unwrap(),
+                10
+            );
+            assert_eq!(connections(&real, &Input::new(source)).unwrap(), 1000);
+        }
 
-        assert_eq!(part_2(SAMPLE_INPUT), 43);
-        assert_eq!(part_2(&real_input), 9206);
+        let input = Input::new(InputSource::Fetch).with_param("connections", 5);
+        assert_eq!(connections(&real, &input).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |(input, connections)| {
+            let points = parse(input).unwrap();
+            let (largest, last_x) = brute_force(&points, *connections);
+
+            assert_eq!(part_1(&points, *connections).unwrap(), largest);
+            assert_eq!(part_2(&points).unwrap(), last_x);
+        });
     }
 }
@@ -1,146 +1,134 @@
-use color_eyre::eyre::Result;
-use geo::{Coord, Intersects, LineString, Polygon};
+use color_eyre::eyre::{Result, eyre};
+use geo::Intersects;
 use itertools::Itertools;
-use rstar::{RTree, RTreeObject, AABB};
-use vek::{Aabr, Extent2, Vec2};
-
-use crate::fetch_input;
-
-fn part_1(input: &str) -> u64 {
-    input
-        .trim()
-        .lines()
-        .map(|l| {
-            let (x, y) = l.split_once(',').unwrap();
-            Vec2::new(x.parse::<u64>().unwrap(), y.parse::<u64>().unwrap())
-        })
-        .array_combinations::<2>()
-        .map(|[a, b]| (Aabr { min: a, max: b }.made_valid().size() + Extent2::new(1, 1)).product())
-        .max()
-        .unwrap()
+use rstar::RTree;
+
+use crate::geometry::{Point2, Rect, RectilinearPolygon, Segment};
+
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<Point2>;
+
+/// Parse one `x,y` red tile position per line, in polygon order
+pub fn parse(input: &str) -> Result<Parsed> {
+    input.trim().lines().map(str::parse).collect()
 }
 
-#[derive(Clone, Copy)]
-struct Edge {
-    p1: [i64; 2],
-    p2: [i64; 2],
+/// Largest rectangle with red tiles in two opposite corners
+pub fn part_1(points: &[Point2]) -> Result<i64> {
+    points
+        .iter()
+        .array_combinations::<2>()
+        .map(|[&a, &b]| Rect::from_corners(a, b).tiles())
+        .max()
+        .ok_or_else(|| eyre!("Need at least two red tiles"))
 }
 
-impl RTreeObject for Edge {
-    type Envelope = AABB<[i64; 2]>;
+/// Check if any part of `edge` lies strictly inside `rect`
+fn crosses_interior(edge: &Segment, rect: &Rect) -> bool {
+    let bounds = edge.bounds();
 
-    fn envelope(&self) -> Self::Envelope {
-        AABB::from_corners(
-            [self.p1[0].min(self.p2[0]), self.p1[1].min(self.p2[1])],
-            [self.p1[0].max(self.p2[0]), self.p1[1].max(self.p2[1])],
-        )
-    }
+    // Edges along a side or ending on one don't count, edges spanning side to side do
+    bounds.min.x < rect.max.x
+        && bounds.max.x > rect.min.x
+        && bounds.min.y < rect.max.y
+        && bounds.max.y > rect.min.y
 }
 
-impl Edge {
-    fn is_horizontal(&self) -> bool {
-        self.p1[1] == self.p2[1]
-    }
-
-    /// Check if this edge crosses a rectangle side strictly inside (not at endpoints)
-    fn crosses_rect_side(&self, left: i64, right: i64, top: i64, bottom: i64) -> bool {
-        if self.is_horizontal() {
-            // Horizontal edge can only cross vertical sides (left/right) of the rect
-            let h_y = self.p1[1];
-            let (h_min_x, h_max_x) = (self.p1[0].min(self.p2[0]), self.p1[0].max(self.p2[0]));
-            // Crosses if y is strictly inside rect and edge spans across the side
-            h_y > top
-                && h_y < bottom
-                && ((h_min_x < left && h_max_x > left) || (h_min_x < right && h_max_x > right))
+/// Check a horizontal or vertical line of tiles stays within the polygon, testing the middle of
+/// each stretch between the edges that touch it
+fn line_inside(polygon: &geo::Polygon<f64>, edge_tree: &RTree<Segment>, line: Segment) -> bool {
+    let along = |point: Point2| {
+        if line.is_horizontal() {
+            point.x
         } else {
-            // Vertical edge can only cross horizontal sides (top/bottom) of the rect
-            let v_x = self.p1[0];
-            let (v_min_y, v_max_y) = (self.p1[1].min(self.p2[1]), self.p1[1].max(self.p2[1]));
-            // Crosses if x is strictly inside rect and edge spans across the side
-            v_x > left
-                && v_x < right
-                && ((v_min_y < top && v_max_y > top) || (v_min_y < bottom && v_max_y > bottom))
+            point.y
         }
-    }
-}
+    };
+    let (start, end) = (
+        along(line.from).min(along(line.to)),
+        along(line.from).max(along(line.to)),
+    );
 
-fn part_2(input: &str) -> i64 {
-    let points: Vec<[i64; 2]> = input
-        .trim()
-        .lines()
-        .map(|l| {
-            let (x, y) = l.split_once(',').unwrap();
-            [x.parse().unwrap(), y.parse().unwrap()]
-        })
-        .collect_vec();
+    let stops = edge_tree
+        .locate_in_envelope_intersecting(&line.bounds().into())
+        .flat_map(|edge| [along(edge.from), along(edge.to)])
+        .filter(|&stop| start < stop && stop < end)
+        .chain([start, end])
+        .sorted_unstable()
+        .dedup();
 
-    let point_tree: RTree<[i64; 2]> = RTree::bulk_load(points.clone());
+    stops.tuple_windows().all(|(a, b)| {
+        let middle = (a + b) as f64 / 2.0;
+        let point = if line.is_horizontal() {
+            geo::Point::new(middle, line.from.y as f64)
+        } else {
+            geo::Point::new(line.from.x as f64, middle)
+        };
+        polygon.intersects(&point)
+    })
+}
 
-    let polygon = Polygon::new(
-        points
-            .iter()
-            .map(|&[x, y]| Coord { x: x as f64, y: y as f64 })
-            .collect::<LineString<f64>>(),
-        vec![],
-    );
+/// Largest rectangle with red tiles in two opposite corners that lies entirely within the
+/// polygon the red tiles trace out
+pub fn part_2(points: &[Point2]) -> Result<i64> {
+    let point_tree = RTree::bulk_load(points.to_vec());
 
-    let edge_tree: RTree<Edge> = RTree::bulk_load(
-        points
-            .iter()
-            .circular_tuple_windows()
-            .map(|(&p1, &p2)| Edge { p1, p2 })
-            .collect(),
-    );
+    let outline = RectilinearPolygon::new(points.to_vec())?;
+    let polygon = geo::Polygon::from(&outline);
+    let edge_tree = RTree::bulk_load(outline.edges().collect());
 
-    let n = points.len();
     let mut max_area: i64 = 0;
 
-    for a in 0..n {
-        for b in (a + 1)..n {
-            let (left, right) = (points[a][0].min(points[b][0]), points[a][0].max(points[b][0]));
-            let (top, bottom) = (points[a][1].min(points[b][1]), points[a][1].max(points[b][1]));
-
-            // Check if any point is strictly inside the rectangle
-            if left + 1 < right && top + 1 < bottom {
-                let interior = AABB::from_corners([left + 1, top + 1], [right - 1, bottom - 1]);
-                if point_tree
-                    .locate_in_envelope_intersecting(&interior)
-                    .next()
-                    .is_some()
-                {
-                    continue;
-                }
-            }
+    for [&a, &b] in points.iter().array_combinations::<2>() {
+        let rect = Rect::from_corners(a, b);
 
-            // Check all 4 corners are inside or on the polygon boundary
-            let corners = [
-                [left, top],
-                [right, top],
-                [left, bottom],
-                [right, bottom],
-            ];
-            if !corners
-                .iter()
-                .map(|&[x, y]| geo::Point::new(x as f64, y as f64))
-                .all(|p| polygon.intersects(&p))
-            {
-                continue;
-            }
+        // Check if any point is strictly inside the rectangle
+        if let Some(interior) = rect.interior()
+            && point_tree
+                .locate_in_envelope_intersecting(&interior.into())
+                .next()
+                .is_some()
+        {
+            continue;
+        }
 
-            // Check no polygon edge crosses the rectangle sides
-            let rect_envelope = AABB::from_corners([left, top], [right, bottom]);
-            if edge_tree
-                .locate_in_envelope_intersecting(&rect_envelope)
-                .any(|edge| edge.crosses_rect_side(left, right, top, bottom))
-            {
-                continue;
-            }
+        // Check all 4 corners are inside or on the polygon boundary
+        if !rect
+            .corners()
+            .into_iter()
+            .all(|corner| polygon.intersects(&geo::Point::from(corner)))
+        {
+            continue;
+        }
+
+        // Check no polygon edge passes through the inside of the rectangle
+        if edge_tree
+            .locate_in_envelope_intersecting(&rect.into())
+            .any(|edge| crosses_interior(edge, &rect))
+        {
+            continue;
+        }
 
-            max_area = max_area.max((right - left + 1) * (bottom - top + 1));
+        // Which leaves the inside entirely in or entirely out of the polygon, even with all
+        // corners on its boundary
+        let size = rect.size();
+        let inside = if size.x > 0 && size.y > 0 {
+            let center = geo::Point::new(
+                (rect.min.x + rect.max.x) as f64 / 2.0,
+                (rect.min.y + rect.max.y) as f64 / 2.0,
+            );
+            polygon.intersects(&center)
+        } else {
+            line_inside(&polygon, &edge_tree, Segment::new(a, b))
+        };
+        if !inside {
+            continue;
         }
+
+        max_area = max_area.max(rect.tiles());
     }
 
-    max_area
+    Ok(max_area)
 }
 
 const SAMPLE_INPUT: &str = "7,1
@@ -153,34 +141,145 @@ const SAMPLE_INPUT: &str = "7,1
 7,3
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(9)?;
-
-    dbg!(part_1(&SAMPLE_INPUT));
-    dbg!(part_1(&input));
-    dbg!(part_2(&SAMPLE_INPUT));
-    dbg!(part_2(&input));
-
-    Ok(())
-}
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng, seq::IteratorRandom};
+
     use super::*;
+    use crate::oracle;
+
+    const SIZE: i64 = 60;
+
+    /// A polygon made of side by side columns, each overlapping the next
+    ///
+    /// Coordinates are even so there are always tiles between two parallel edges, like in the
+    /// real input.
+    fn generate(rng: &mut StdRng) -> String {
+        let columns = rng.random_range(1..6);
+        let even = |rng: &mut StdRng| rng.random_range(0..=SIZE / 2) * 2;
+
+        let xs = (0..=SIZE / 2)
+            .map(|x| x * 2)
+            .choose_multiple(rng, columns + 1)
+            .into_iter()
+            .sorted()
+            .collect_vec();
+
+        let mut spans: Vec<(i64, i64)> = vec![];
+        while spans.len() < columns {
+            let (bottom, top) = (even(rng), even(rng));
+            let overlaps = spans
+                .last()
+                .is_none_or(|&(last_bottom, last_top)| bottom < last_top && last_bottom < top);
+
+            if bottom < top && overlaps {
+                spans.push((bottom, top));
+            }
+        }
+
+        // Along the tops left to right, then back along the bottoms
+        let mut points = (0..columns)
+            .flat_map(|i| [[xs[i], spans[i].1], [xs[i + 1], spans[i].1]])
+            .chain(
+                (0..columns)
+                    .rev()
+                    .flat_map(|i| [[xs[i + 1], spans[i].0], [xs[i], spans[i].0]]),
+            )
+            .collect_vec();
+
+        // Equal heights side by side leave duplicate and collinear corners behind
+        points.dedup();
+        let mut i = 0;
+        while i < points.len() {
+            let [prev, point, next] =
+                [i + points.len() - 1, i, i + 1].map(|j| points[j % points.len()]);
+
+            if (prev[0] == point[0] && point[0] == next[0])
+                || (prev[1] == point[1] && point[1] == next[1])
+            {
+                points.remove(i);
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+
+        points.iter().map(|[x, y]| format!("{x},{y}\n")).collect()
+    }
+
+    /// Mark every tile inside or on the polygon, then try every rectangle tile by tile
+    fn brute_force(red: &[Point2]) -> (i64, i64) {
+        let edges = red.iter().circular_tuple_windows().collect_vec();
+        let between = |n: i64, a: i64, b: i64| a.min(b) <= n && n <= a.max(b);
+
+        let inside = (0..=SIZE)
+            .map(|y| {
+                (0..=SIZE)
+                    .map(|x| {
+                        let on_edge = edges
+                            .iter()
+                            .any(|(p, q)| between(x, p.x, q.x) && between(y, p.y, q.y));
+
+                        // Count vertical edges to the right, half open so corners count once
+                        let crossings = edges
+                            .iter()
+                            .filter(|(p, q)| p.x == q.x && p.x > x)
+                            .filter(|(p, q)| p.y.min(q.y) <= y && y < p.y.max(q.y))
+                            .count();
+
+                        on_edge || crossings % 2 == 1
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let rectangles = red
+            .iter()
+            .array_combinations::<2>()
+            .map(|[a, b]| {
+                let xs = a.x.min(b.x)..=a.x.max(b.x);
+                let ys = a.y.min(b.y)..=a.y.max(b.y);
+                let area = (xs.end() - xs.start() + 1) * (ys.end() - ys.start() + 1);
+                let filled = ys
+                    .clone()
+                    .all(|y| xs.clone().all(|x| inside[y as usize][x as usize]));
+
+                (area, filled)
+            })
+            .collect_vec();
+
+        (
+            rectangles.iter().map(|&(area, _)| area).max().unwrap(),
+            rectangles
+                .iter()
+                .filter(|&&(_, filled)| filled)
+                .map(|&(area, _)| area)
+                .max()
+                .unwrap(),
+        )
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(4).unwrap();
-
-        assert_eq!(part_1(SAMPLE_INPUT), 13);
-        assert_eq!(part_1(&real_input), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 50);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(4).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 24);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let red = parse(input).unwrap();
+            let (any, filled) = brute_force(&red);
 
-        assert_eq!(part_2(SAMPLE_INPUT), 43);
-        assert_eq!(part_2(&real_input), 9206);
+            assert_eq!(part_1(&red).unwrap(), any);
+            assert_eq!(part_2(&red).unwrap(), filled);
+        });
     }
 }
@@ -1,63 +1,100 @@
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{Result, eyre};
 use good_lp::{Expression, Solution, SolverModel, constraint, default_solver, variable};
 use itertools::Itertools;
 use pathfinding::prelude::bfs;
-use rayon::prelude::*;
 
-use crate::fetch_input;
+/// One line of the manual
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    /// Target indicator light pattern, `true` for `#`
+    pub lights: Vec<bool>,
+    /// Indices of the lights/counters each button toggles
+    pub buttons: Vec<Vec<usize>>,
+    /// Target joltage for each counter
+    pub joltages: Vec<u32>,
+}
 
-fn part_1(input: &str) -> usize {
-    let input = input.trim();
+/// Strip the surrounding brackets from `[...]`, `(...)` or `{...}`
+fn strip_brackets(s: &str) -> Result<&str> {
+    s.get(1..s.len().saturating_sub(1))
+        .ok_or_else(|| eyre!("Expected bracketed group, got {s:?}"))
+}
 
-    let mut sum = 0;
+/// What [`parse`] produces, shared by both parts
+pub type Parsed = Vec<Machine>;
 
-    for line in input.lines() {
-        let (target_lights, rest) = line.split_once(' ').unwrap();
-        let (buttons, _joltage_requirements) = rest.rsplit_once(' ').unwrap();
+/// Parse one machine per line: `[lights] (buttons)... {joltages}`
+pub fn parse(input: &str) -> Result<Parsed> {
+    input
+        .trim()
+        .lines()
+        .map(|line| {
+            let (lights, rest) = line
+                .split_once(' ')
+                .ok_or_else(|| eyre!("Invalid machine {line:?}"))?;
+            let (buttons, joltages) = rest
+                .rsplit_once(' ')
+                .ok_or_else(|| eyre!("Invalid machine {line:?}"))?;
 
-        let target_lights = &target_lights[1..target_lights.len() - 1];
-        let length = target_lights.len();
-        // Map target_lights . to 0 and # to 1 bitmap
-        let mut target_lights_map = 0u16;
-        for (i, target_light) in target_lights.chars().rev().enumerate() {
-            target_lights_map |= ((target_light == '#') as u16) << (i as u16);
-        }
+            let lights = strip_brackets(lights)?.chars().map(|c| c == '#').collect();
 
-        // println!("{target_lights} {target_lights_map:b}");
+            let buttons = buttons
+                .split(' ')
+                .map(|button| {
+                    strip_brackets(button)?
+                        .split(',')
+                        .map(|n| Ok(n.parse()?))
+                        .collect()
+                })
+                .collect::<Result<_>>()?;
 
-        let buttons = buttons
-            .split(' ')
-            .map(|buttons| {
-                let buttons = &buttons[1..buttons.len() - 1];
-                let mut button_map = 0u16;
+            let joltages = strip_brackets(joltages)?
+                .split(',')
+                .map(|n| Ok(n.parse()?))
+                .collect::<Result<_>>()?;
 
-                for n in buttons.split(',').map(|n| n.parse::<u16>().unwrap()) {
-                    let pos = length as u16 - 1 - n;
-                    button_map |= 1 << pos;
-                }
+            Ok(Machine {
+                lights,
+                buttons,
+                joltages,
+            })
+        })
+        .collect()
+}
 
-                // println!("{buttons} {button_map:b}");
+/// Fewest total button presses to configure every machine's indicator lights
+pub fn part_1(machines: &[Machine]) -> Result<usize> {
+    let mut sum = 0;
 
-                button_map
-            })
+    for machine in machines {
+        // Map target lights . to 0 and # to 1 bitmap, light i at bit i
+        let target_lights_map = machine
+            .lights
+            .iter()
+            .enumerate()
+            .fold(0u16, |map, (i, &on)| map | ((on as u16) << i));
+
+        let buttons = machine
+            .buttons
+            .iter()
+            .map(|button| button.iter().fold(0u16, |map, &n| map | 1 << n))
             .collect_vec();
 
         let result = bfs(
             &0u16,
             |current| buttons.iter().map(|button| current ^ button).collect_vec(),
-            // |current| 1,
             |&current| current == target_lights_map,
-        );
+        )
+        .ok_or_else(|| eyre!("Lights {:?} can't be reached", machine.lights))?;
 
-        let length = result.unwrap().len() - 1;
-        sum += length;
+        sum += result.len() - 1;
     }
 
-    sum
+    Ok(sum)
 }
 
 /// Solve using Integer Linear Programming with CBC solver
-fn solve_ilp(buttons: &[Vec<usize>], targets: &[u32]) -> usize {
+fn solve_ilp(buttons: &[Vec<usize>], targets: &[u32]) -> Result<usize> {
     let num_counters = targets.len();
     let num_buttons = buttons.len();
 
@@ -85,43 +122,20 @@ fn solve_ilp(buttons: &[Vec<usize>], targets: &[u32]) -> usize {
     }
 
     // Solve ILP
-    let solution = model.solve().unwrap();
+    let solution = model.solve()?;
 
     // Sum up the integer button presses
-    button_vars
+    Ok(button_vars
         .iter()
         .map(|&v| solution.value(v).round() as usize)
-        .sum()
+        .sum())
 }
 
-fn part_2(input: &str) -> usize {
-    let input = input.trim();
-
-    input
-        .lines()
-        .map(|line| {
-            let (_target_lights, rest) = line.split_once(' ').unwrap();
-            let (buttons, joltage_requirements) = rest.rsplit_once(' ').unwrap();
-
-            let joltage_requirements = &joltage_requirements[1..joltage_requirements.len() - 1];
-            let targets: Vec<u32> = joltage_requirements
-                .split(',')
-                .map(|joltage| joltage.parse().unwrap())
-                .collect_vec();
-
-            let buttons: Vec<Vec<usize>> = buttons
-                .split(' ')
-                .map(|buttons| {
-                    let buttons = &buttons[1..buttons.len() - 1];
-                    buttons
-                        .split(",")
-                        .map(|button| button.parse().unwrap())
-                        .collect_vec()
-                })
-                .collect_vec();
-
-            solve_ilp(&buttons, &targets)
-        })
+/// Fewest total button presses to reach every machine's joltage requirements
+pub fn part_2(machines: &[Machine]) -> Result<usize> {
+    machines
+        .iter()
+        .map(|machine| solve_ilp(&machine.buttons, &machine.joltages))
         .sum()
 }
 
@@ -130,33 +144,123 @@ const SAMPLE_INPUT: &str = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
 ";
 
-pub fn main() -> Result<()> {
-    let input = fetch_input(10)?;
-
-    // dbg!(part_1(&SAMPLE_INPUT));
-    // dbg!(part_1(&input));
-    dbg!(part_2(&SAMPLE_INPUT));
-    dbg!(part_2(&input));
-
-    Ok(())
-}
+/// Examples from the puzzle text, by name
+pub const SAMPLES: &[(&str, &str)] = &[("example", SAMPLE_INPUT)];
 
 #[cfg(test)]
 mod tests {
+    use rand::{Rng, rngs::StdRng};
+
     use super::*;
+    use crate::oracle;
+
+    /// Machines whose lights and joltages come from pressing random buttons, so they're reachable
+    fn generate(rng: &mut StdRng) -> String {
+        (0..rng.random_range(1..4))
+            .map(|_| {
+                let counters = rng.random_range(1..=6);
+                let buttons = (0..rng.random_range(1..=5))
+                    .map(|_| {
+                        let button = (0..counters).filter(|_| rng.random_bool(0.4)).collect_vec();
+                        match button.is_empty() {
+                            true => vec![rng.random_range(0..counters)],
+                            false => button,
+                        }
+                    })
+                    .collect_vec();
+
+                let mut lights = vec![false; counters];
+                let mut joltages = vec![0; counters];
+                for button in &buttons {
+                    let presses = rng.random_range(0..4);
+                    for &counter in button {
+                        lights[counter] ^= presses % 2 == 1;
+                        joltages[counter] += presses;
+                    }
+                }
+
+                format!(
+                    "[{}] {} {{{}}}\n",
+                    lights.iter().map(|&on| if on { '#' } else { '.' }).join(""),
+                    buttons
+                        .iter()
+                        .map(|button| format!("({})", button.iter().join(",")))
+                        .join(" "),
+                    joltages.iter().join(",")
+                )
+            })
+            .collect()
+    }
+
+    /// Try every set of buttons, since pressing one twice does nothing
+    fn brute_force_lights(machine: &Machine) -> usize {
+        (0..1usize << machine.buttons.len())
+            .filter(|pressed| {
+                let mut lights = vec![false; machine.lights.len()];
+                for (i, button) in machine.buttons.iter().enumerate() {
+                    if pressed & (1 << i) != 0 {
+                        button.iter().for_each(|&light| lights[light] ^= true);
+                    }
+                }
+                lights == machine.lights
+            })
+            .map(|pressed| pressed.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    /// Try every number of presses of each button that doesn't overshoot a counter
+    fn brute_force_joltages(buttons: &[Vec<usize>], remaining: &mut [u32]) -> Option<usize> {
+        // Give up as soon as a counter can't be reached by the buttons left
+        let unreachable = |c: usize| remaining[c] > 0 && !buttons.iter().any(|b| b.contains(&c));
+        if (0..remaining.len()).any(unreachable) {
+            return None;
+        }
+
+        let Some((button, rest)) = buttons.split_first() else {
+            return Some(0);
+        };
+
+        let most = button.iter().map(|&c| remaining[c]).min().unwrap();
+        let mut fewest = None;
+
+        for presses in 0..=most {
+            button.iter().for_each(|&c| remaining[c] -= presses);
+
+            if let Some(more) = brute_force_joltages(rest, remaining) {
+                let total = presses as usize + more;
+                fewest = Some(fewest.map_or(total, |f: usize| f.min(total)));
+            }
+
+            button.iter().for_each(|&c| remaining[c] += presses);
+        }
+
+        fewest
+    }
 
     #[test]
     fn test_part_1() {
-        let real_input = fetch_input(10).unwrap();
-
-        assert_eq!(part_1(SAMPLE_INPUT), 13);
-        assert_eq!(part_1(&real_input), 1533);
+        assert_eq!(part_1(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 7);
     }
 
     #[test]
     fn test_part_2() {
-        let real_input = fetch_input(10).unwrap();
+        assert_eq!(part_2(&parse(SAMPLE_INPUT).unwrap()).unwrap(), 33);
+    }
 
-        assert_eq!(part_2(SAMPLE_INPUT), 33);
+    #[test]
+    fn test_matches_brute_force() {
+        oracle::check(generate, |input| {
+            let machines = parse(input).unwrap();
+
+            let lights: usize = machines.iter().map(brute_force_lights).sum();
+            let joltages: usize = machines
+                .iter()
+                .map(|m| brute_force_joltages(&m.buttons, &mut m.joltages.clone()).unwrap())
+                .sum();
+
+            assert_eq!(part_1(&machines).unwrap(), lights);
+            assert_eq!(part_2(&machines).unwrap(), joltages);
+        });
     }
 }
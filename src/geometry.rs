@@ -0,0 +1,470 @@
+//! Integer points, vectors, boxes, segments and polygons for grid and geometry puzzles
+//!
+//! Conversions to `rstar` and `geo` types are behind the features of the same name.
+
+use std::{
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use color_eyre::eyre::{Report, Result, bail, eyre};
+use itertools::Itertools;
+
+/// A position on the integer plane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// The difference between two [`Point2`]s
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A position in integer space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+/// The difference between two [`Point3`]s
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point2 {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance_squared(self, other: Self) -> i64 {
+        (other - self).length_squared()
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> i64 {
+        (other - self).manhattan_length()
+    }
+}
+
+impl Vec2 {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn length_squared(self) -> i64 {
+        self.x * self.x + self.y * self.y
+    }
+
+    pub fn manhattan_length(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+}
+
+impl Point3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn distance_squared(self, other: Self) -> i64 {
+        (other - self).length_squared()
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> i64 {
+        (other - self).manhattan_length()
+    }
+}
+
+impl Vec3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn length_squared(self) -> i64 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    pub fn manhattan_length(self) -> i64 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+}
+
+/// Point minus point is a vector, point plus or minus a vector is a point, and vectors add,
+/// negate and scale
+macro_rules! arithmetic {
+    ($point:ident, $vec:ident, $($field:ident),+) => {
+        impl Sub for $point {
+            type Output = $vec;
+
+            fn sub(self, other: Self) -> $vec {
+                $vec { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Add<$vec> for $point {
+            type Output = Self;
+
+            fn add(self, offset: $vec) -> Self {
+                Self { $($field: self.$field + offset.$field),+ }
+            }
+        }
+
+        impl Sub<$vec> for $point {
+            type Output = Self;
+
+            fn sub(self, offset: $vec) -> Self {
+                Self { $($field: self.$field - offset.$field),+ }
+            }
+        }
+
+        impl Add for $vec {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $vec {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Neg for $vec {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl Mul<i64> for $vec {
+            type Output = Self;
+
+            fn mul(self, factor: i64) -> Self {
+                Self { $($field: self.$field * factor),+ }
+            }
+        }
+    };
+}
+
+arithmetic!(Point2, Vec2, x, y);
+arithmetic!(Point3, Vec3, x, y, z);
+
+/// Parse comma separated integers, as puzzle inputs write coordinates
+fn coordinates<const N: usize>(s: &str) -> Result<[i64; N]> {
+    let parts = s.trim().split(',').collect_vec();
+    let parts: [&str; N] = parts.try_into().map_err(|parts: Vec<_>| {
+        eyre!(
+            "Expected {N} comma separated coordinates, got {} in {s:?}",
+            parts.len()
+        )
+    })?;
+
+    let mut coordinates = [0; N];
+    for (coordinate, part) in coordinates.iter_mut().zip(parts) {
+        *coordinate = part
+            .trim()
+            .parse()
+            .map_err(|e| eyre!("Bad coordinate {part:?} in {s:?}: {e}"))?;
+    }
+
+    Ok(coordinates)
+}
+
+/// `x,y`
+impl FromStr for Point2 {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [x, y] = coordinates(s)?;
+        Ok(Self { x, y })
+    }
+}
+
+/// `x,y,z`
+impl FromStr for Point3 {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [x, y, z] = coordinates(s)?;
+        Ok(Self { x, y, z })
+    }
+}
+
+/// Written back the way it's parsed, `x,y`
+impl fmt::Display for Point2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// Written back the way it's parsed, `x,y,z`
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+/// An axis-aligned box of grid tiles, covering `min` to `max` inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl Rect {
+    /// The box with two opposite corners at `a` and `b`, in any order
+    pub fn from_corners(a: Point2, b: Point2) -> Self {
+        Self {
+            min: Point2::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point2::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// From `min` to `max`, zero along an axis the box is one tile thick in
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// Number of tiles covered, edges included
+    pub fn tiles(&self) -> i64 {
+        let size = self.size();
+        (size.x + 1) * (size.y + 1)
+    }
+
+    pub fn contains(&self, point: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// The tiles not on the edge, if there are any
+    pub fn interior(&self) -> Option<Self> {
+        let one = Vec2::new(1, 1);
+        let size = self.size();
+
+        (size.x >= 2 && size.y >= 2).then(|| Self {
+            min: self.min + one,
+            max: self.max - one,
+        })
+    }
+
+    /// Corners in reading order: top left, top right, bottom left, bottom right
+    pub fn corners(&self) -> [Point2; 4] {
+        let (min, max) = (self.min, self.max);
+        [
+            min,
+            Point2::new(max.x, min.y),
+            Point2::new(min.x, max.y),
+            max,
+        ]
+    }
+}
+
+/// A straight line between two points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub from: Point2,
+    pub to: Point2,
+}
+
+impl Segment {
+    pub const fn new(from: Point2, to: Point2) -> Self {
+        Self { from, to }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.from.y == self.to.y
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        self.from.x == self.to.x
+    }
+
+    /// The smallest box covering the whole segment
+    pub fn bounds(&self) -> Rect {
+        Rect::from_corners(self.from, self.to)
+    }
+}
+
+/// A closed polygon whose edges are all horizontal or vertical
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RectilinearPolygon {
+    vertices: Vec<Point2>,
+}
+
+impl RectilinearPolygon {
+    /// Vertices in order around the polygon, the last one joining back up with the first
+    pub fn new(vertices: Vec<Point2>) -> Result<Self> {
+        if vertices.len() < 4 {
+            bail!(
+                "A polygon needs at least 4 vertices, got {}",
+                vertices.len()
+            );
+        }
+
+        let polygon = Self { vertices };
+        if let Some(edge) = polygon
+            .edges()
+            .find(|edge| edge.is_horizontal() == edge.is_vertical())
+        {
+            bail!(
+                "Edge from {} to {} isn't a horizontal or vertical line",
+                edge.from,
+                edge.to
+            );
+        }
+
+        Ok(polygon)
+    }
+
+    pub fn vertices(&self) -> &[Point2] {
+        &self.vertices
+    }
+
+    /// Every edge including the closing one, in order
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.vertices
+            .iter()
+            .circular_tuple_windows()
+            .map(|(&from, &to)| Segment::new(from, to))
+    }
+}
+
+#[cfg(feature = "rstar")]
+mod rstar_conversions {
+    use rstar::{AABB, RTreeObject};
+
+    use super::*;
+
+    /// So points can go in an `RTree` and bound an `AABB` directly
+    impl rstar::Point for Point2 {
+        type Scalar = i64;
+        const DIMENSIONS: usize = 2;
+
+        fn generate(mut generator: impl FnMut(usize) -> i64) -> Self {
+            Self::new(generator(0), generator(1))
+        }
+
+        fn nth(&self, index: usize) -> i64 {
+            match index {
+                0 => self.x,
+                1 => self.y,
+                _ => unreachable!("2D points have no axis {index}"),
+            }
+        }
+
+        fn nth_mut(&mut self, index: usize) -> &mut i64 {
+            match index {
+                0 => &mut self.x,
+                1 => &mut self.y,
+                _ => unreachable!("2D points have no axis {index}"),
+            }
+        }
+    }
+
+    impl From<Rect> for AABB<Point2> {
+        fn from(rect: Rect) -> Self {
+            AABB::from_corners(rect.min, rect.max)
+        }
+    }
+
+    impl RTreeObject for Segment {
+        type Envelope = AABB<Point2>;
+
+        fn envelope(&self) -> Self::Envelope {
+            self.bounds().into()
+        }
+    }
+}
+
+#[cfg(feature = "geo")]
+mod geo_conversions {
+    use super::*;
+
+    impl From<Point2> for geo::Coord<f64> {
+        fn from(point: Point2) -> Self {
+            geo::Coord {
+                x: point.x as f64,
+                y: point.y as f64,
+            }
+        }
+    }
+
+    impl From<Point2> for geo::Point<f64> {
+        fn from(point: Point2) -> Self {
+            geo::Point(point.into())
+        }
+    }
+
+    impl From<&RectilinearPolygon> for geo::Polygon<f64> {
+        fn from(polygon: &RectilinearPolygon) -> Self {
+            let exterior = polygon
+                .vertices
+                .iter()
+                .map(|&vertex| geo::Coord::from(vertex))
+                .collect();
+
+            geo::Polygon::new(exterior, vec![])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3,-4".parse::<Point2>().unwrap(), Point2::new(3, -4));
+        assert_eq!(" 1, 2,3\n".parse::<Point3>().unwrap(), Point3::new(1, 2, 3));
+        assert_eq!(Point3::new(1, 2, 3).to_string(), "1,2,3");
+
+        let error = "1,2,3".parse::<Point2>().unwrap_err();
+        assert!(error.to_string().contains("Expected 2"));
+        assert!("1,x".parse::<Point2>().is_err());
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::from_corners(Point2::new(11, 1), Point2::new(2, 5));
+        assert_eq!(rect.min, Point2::new(2, 1));
+        assert_eq!(rect.size(), Vec2::new(9, 4));
+        assert_eq!(rect.tiles(), 50);
+        assert!(rect.contains(Point2::new(11, 3)));
+        assert!(!rect.contains(Point2::new(12, 3)));
+
+        assert_eq!(
+            rect.interior(),
+            Some(Rect::from_corners(Point2::new(3, 2), Point2::new(10, 4)))
+        );
+        let line = Rect::from_corners(Point2::new(0, 0), Point2::new(5, 1));
+        assert_eq!(line.interior(), None);
+    }
+
+    #[test]
+    fn test_polygon() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)].map(|(x, y)| Point2::new(x, y));
+
+        let polygon = RectilinearPolygon::new(square.to_vec()).unwrap();
+        let edges = polygon.edges().collect_vec();
+        assert_eq!(edges.len(), 4);
+        assert_eq!(edges[3], Segment::new(square[3], square[0]));
+        assert!(edges[3].is_vertical());
+
+        let mut diagonal = square.to_vec();
+        diagonal[2] = Point2::new(3, 4);
+        assert!(RectilinearPolygon::new(diagonal).is_err());
+        assert!(RectilinearPolygon::new(square[..3].to_vec()).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::{
+    fs::OpenOptions,
+    io::{Read, Seek, Write},
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use http_cache_surf::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
+use surf::{Request, StatusCode};
+
+use crate::{
+    YEAR,
+    puzzle::{articles, strip_tags},
+};
+
+const DEFAULT_USER_AGENT: &str = "github.com/teevik/advent-of-code-2025";
+
+pub fn input_path(day: u8) -> String {
+    format!("/{YEAR}/day/{day}/input")
+}
+
+/// The one HTTP client all requests to the site go through
+///
+/// Responses are cached on disk, and requests that actually hit the network are spaced at
+/// least `min_interval` apart, even across processes.
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub user_agent: String,
+    pub cache_dir: PathBuf,
+    pub min_interval: Duration,
+}
+
+impl Client {
+    /// Configure from the environment:
+    ///
+    /// - `SESSION`: session cookie, required for inputs and personal pages
+    /// - `AOC_USER_AGENT`: full User-Agent, or `AOC_CONTACT` to append contact info to the default
+    /// - `AOC_MIN_INTERVAL`: seconds between uncached requests, 5 by default
+    /// - `AOC_BASE_URL`: defaults to `https://adventofcode.com`
+    /// - `AOC_CACHE_DIR`: where responses are cached, `cache` by default
+    pub fn from_env() -> Result<Self> {
+        let user_agent = match (
+            std::env::var("AOC_USER_AGENT"),
+            std::env::var("AOC_CONTACT"),
+        ) {
+            (Ok(user_agent), _) => user_agent,
+            (_, Ok(contact)) => format!("{DEFAULT_USER_AGENT} by {contact}"),
+            _ => DEFAULT_USER_AGENT.to_owned(),
+        };
+
+        let min_interval = match std::env::var("AOC_MIN_INTERVAL") {
+            Ok(seconds) => Duration::try_from_secs_f64(
+                seconds
+                    .parse()
+                    .wrap_err("AOC_MIN_INTERVAL should be a number of seconds")?,
+            )?,
+            Err(_) => Duration::from_secs(5),
+        };
+
+        Ok(Self {
+            base_url: std::env::var("AOC_BASE_URL")
+                .unwrap_or_else(|_| "https://adventofcode.com".to_owned()),
+            session: std::env::var("SESSION").ok(),
+            user_agent,
+            cache_dir: std::env::var_os("AOC_CACHE_DIR")
+                .map_or_else(|| PathBuf::from("cache"), PathBuf::from),
+            min_interval,
+        })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Key the HTTP cache stores a GET of `path` under
+    pub fn cache_key(&self, path: &str) -> String {
+        format!("GET:{}", self.url(path))
+    }
+
+    pub fn is_cached(&self, path: &str) -> bool {
+        cacache::metadata_sync(&self.cache_dir, self.cache_key(path)).is_ok_and(|m| m.is_some())
+    }
+
+    /// GET `path` through the cache, throttling if it may hit the network
+    pub fn get(&self, path: &str, mode: CacheMode) -> Result<String> {
+        let cached = self.is_cached(path);
+        let hits_network = match mode {
+            CacheMode::ForceCache => !cached,
+            CacheMode::OnlyIfCached => false,
+            _ => true,
+        };
+
+        // Inputs never change, so there's no reason to ask for one twice
+        if cached && hits_network && path.ends_with("/input") {
+            bail!("{path} is already cached, refusing to download it again");
+        }
+
+        if hits_network {
+            self.throttle()?;
+        }
+
+        let result = self.send(&self.cache_client(mode), surf::get(self.url(path)).build());
+
+        // Some error pages (like 404 before a day unlocks) are cacheable by default,
+        // don't let them stick around for `ForceCache` to serve forever
+        if result.is_err() && !cached {
+            let _ = cacache::remove_sync(&self.cache_dir, self.cache_key(path));
+        }
+
+        result
+    }
+
+    /// The cached response for `path` with its status, without touching the network
+    pub fn cached(&self, path: &str) -> Result<(StatusCode, String)> {
+        if !self.is_cached(path) {
+            bail!("{} is not cached", self.url(path));
+        }
+
+        let request = surf::get(self.url(path)).build();
+        self.send_any(&self.cache_client(CacheMode::OnlyIfCached), request)
+    }
+
+    fn cache_client(&self, mode: CacheMode) -> surf::Client {
+        surf::client().with(Cache(HttpCache {
+            mode,
+            manager: CACacheManager::new(self.cache_dir.clone(), false),
+            options: HttpCacheOptions::default(),
+        }))
+    }
+
+    /// Send `request` with our User-Agent and session, failing on error statuses
+    fn send(&self, client: &surf::Client, request: Request) -> Result<String> {
+        let method = request.method();
+        let url = request.url().clone();
+
+        let (status, text) = self.send_any(client, request)?;
+
+        if !status.is_success() {
+            bail!("{method} {url} returned {status}: {}", text.trim());
+        }
+
+        Ok(text)
+    }
+
+    /// Send `request` with our User-Agent and session, whatever status comes back
+    fn send_any(
+        &self,
+        client: &surf::Client,
+        mut request: Request,
+    ) -> Result<(StatusCode, String)> {
+        request.insert_header("User-Agent", self.user_agent.as_str());
+        if let Some(session) = &self.session {
+            request.insert_header("COOKIE", format!("session={session}"));
+        }
+
+        let mut response = smol::block_on(client.send(request)).map_err(|e| eyre!("{e}"))?;
+
+        let text = smol::block_on(response.body_string()).map_err(|e| eyre!("{e}"))?;
+
+        Ok((response.status(), text))
+    }
+
+    /// Fetch a day's input, downloading it at most once
+    pub fn input(&self, day: u8) -> Result<String> {
+        if self.session.is_none() {
+            bail!("should have a session token set");
+        }
+
+        self.get(&input_path(day), CacheMode::ForceCache)
+    }
+
+    /// Submit an answer, returning the site's verdict as plain text
+    ///
+    /// Never cached, but throttled like any other request.
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<String> {
+        if self.session.is_none() {
+            bail!("should have a session token set");
+        }
+
+        self.throttle()?;
+
+        let form = [("level", part.to_string()), ("answer", answer.to_owned())];
+        let body = surf::Body::from_form(&form).map_err(|e| eyre!("{e}"))?;
+        let request = surf::post(self.url(&format!("/{YEAR}/day/{day}/answer")))
+            .body(body)
+            .build();
+
+        let html = self.send(&surf::client(), request)?;
+        let verdict = articles(&html)
+            .first()
+            .map(|article| strip_tags(article))
+            .ok_or_else(|| eyre!("No verdict in the answer response"))?;
+
+        Ok(verdict.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Wait until `min_interval` has passed since the last uncached request from any process
+    fn throttle(&self) -> Result<()> {
+        std::fs::create_dir_all(&self.cache_dir)?;
+
+        // Holding the lock while sleeping queues up other processes behind us
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.cache_dir.join("last-request"))?;
+        file.lock()?;
+
+        let mut last = String::new();
+        file.read_to_string(&mut last)?;
+
+        if let Ok(last) = last.trim().parse::<u64>() {
+            let next = UNIX_EPOCH + Duration::from_millis(last) + self.min_interval;
+
+            if let Ok(wait) = next.duration_since(SystemTime::now()) {
+                thread::sleep(wait);
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{now}")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn client(name: &str) -> Client {
+        let cache_dir =
+            std::env::temp_dir().join(format!("aoc-http-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        Client {
+            base_url: "http://127.0.0.1:9".to_owned(),
+            session: Some("test".to_owned()),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            cache_dir,
+            min_interval: Duration::from_millis(200),
+        }
+    }
+
+    #[test]
+    fn test_throttle() {
+        let client = client("throttle");
+
+        let start = Instant::now();
+        client.throttle().unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // Timestamps are stored in whole milliseconds, so allow a little slack
+        let start = Instant::now();
+        client.throttle().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_refuses_to_redownload_input() {
+        let client = client("redownload");
+        let path = "/2025/day/1/input";
+
+        assert!(!client.is_cached(path));
+        cacache::write_sync(&client.cache_dir, client.cache_key(path), b"L68\n").unwrap();
+        assert!(client.is_cached(path));
+
+        let error = client.get(path, CacheMode::Reload).unwrap_err();
+        assert!(error.to_string().contains("refusing"));
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{Cursor, Read},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+
+use crate::fetch_input;
+
+/// Where a day's puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Real puzzle input, downloaded or loaded from the cache
+    Fetch,
+    /// Everything piped into stdin
+    Stdin,
+    /// A file on disk
+    File(PathBuf),
+    /// One of the day's embedded samples, by name (`None` for the first one)
+    Sample(Option<String>),
+}
+
+/// Parses `fetch`, `-` (stdin), `sample`, `sample:<name>` or anything else as a file path
+impl FromStr for InputSource {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "fetch" => Self::Fetch,
+            "-" => Self::Stdin,
+            "sample" => Self::Sample(None),
+            _ => match s.strip_prefix("sample:") {
+                Some(name) => Self::Sample(Some(name.to_owned())),
+                None => Self::File(PathBuf::from(s)),
+            },
+        })
+    }
+}
+
+/// Formats the same way it parses
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch => write!(f, "fetch"),
+            Self::Stdin => write!(f, "-"),
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Sample(None) => write!(f, "sample"),
+            Self::Sample(Some(name)) => write!(f, "sample:{name}"),
+        }
+    }
+}
+
+/// Everything the runner hands to a day: the input source and puzzle parameters
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub source: InputSource,
+    /// Puzzle-specific parameters, e.g. `connections` for day 8
+    pub params: HashMap<String, String>,
+}
+
+impl Input {
+    pub fn new(source: InputSource) -> Self {
+        Self {
+            source,
+            params: HashMap::new(),
+        }
+    }
+
+    pub fn with_param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.insert(key.to_owned(), value.to_string());
+        self
+    }
+
+    /// Read the input text, looking samples up in the day's `samples` table
+    pub fn read(&self, day: u8, samples: &[(&str, &str)]) -> Result<String> {
+        match &self.source {
+            InputSource::Fetch => fetch_input(day),
+            InputSource::Stdin => {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+            InputSource::File(path) => std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("Failed to read {}", path.display())),
+            InputSource::Sample(name) => {
+                let sample = match name {
+                    None => samples.first(),
+                    Some(name) => samples.iter().find(|(n, _)| n == name),
+                };
+
+                sample.map(|(_, text)| text.to_string()).ok_or_else(|| {
+                    let names = samples.iter().map(|(n, _)| *n).collect::<Vec<_>>();
+                    eyre!("Day {day} has no sample {name:?}, available: {names:?}")
+                })
+            }
+        }
+    }
+
+    /// Open the input to read as it goes, which only files and stdin can do without holding all
+    /// of it in memory
+    pub fn reader(&self, day: u8, samples: &[(&str, &str)]) -> Result<Box<dyn Read>> {
+        match &self.source {
+            InputSource::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            InputSource::File(path) => {
+                Ok(Box::new(File::open(path).wrap_err_with(|| {
+                    format!("Failed to open {}", path.display())
+                })?))
+            }
+            InputSource::Fetch | InputSource::Sample(_) => {
+                Ok(Box::new(Cursor::new(self.read(day, samples)?)))
+            }
+        }
+    }
+
+    /// Parse a puzzle parameter, `None` if it wasn't supplied
+    pub fn param<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.params
+            .get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| eyre!("Invalid value {value:?} for parameter {key}: {e}"))
+            })
+            .transpose()
+    }
+}
@@ -3,39 +3,67 @@
 #![feature(new_range_api)]
 #![feature(array_windows)]
 
+pub mod bench;
+pub mod cache;
+#[cfg(feature = "day01")]
 pub mod day01;
+#[cfg(feature = "day01")]
+pub mod day01_animation;
+#[cfg(feature = "day01")]
 pub mod day01_fast;
+#[cfg(feature = "day01")]
+pub mod day01_generator;
+#[cfg(feature = "day01")]
+pub mod day01_parallel;
+#[cfg(feature = "day01")]
+pub mod day01_stream;
+#[cfg(feature = "day02")]
 pub mod day02;
+#[cfg(feature = "day02")]
+pub mod day02_fast;
+#[cfg(feature = "day03")]
 pub mod day03;
+#[cfg(feature = "day04")]
 pub mod day04;
+#[cfg(feature = "day05")]
 pub mod day05;
+#[cfg(feature = "day06")]
 pub mod day06;
+#[cfg(feature = "day07")]
 pub mod day07;
+#[cfg(feature = "day08")]
 pub mod day08;
+#[cfg(feature = "day09")]
 pub mod day09;
+#[cfg(feature = "day10")]
 pub mod day10;
+pub mod geometry;
+pub mod http;
+pub mod input;
+#[cfg(test)]
+// Unused when no days are enabled
+#[allow(dead_code)]
+mod oracle;
+pub mod prefetch;
+pub mod puzzle;
+pub mod registry;
+pub mod runner;
+pub mod stats;
 
-use std::path::PathBuf;
+use color_eyre::Result;
+use http_cache_surf::CacheMode;
 
-use color_eyre::{Result, eyre::eyre};
-use http_cache_surf::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
+/// Event year, in every URL on the site
+pub const YEAR: u16 = 2025;
 
-pub fn fetch_input(day: u8) -> Result<String> {
-    let session = std::env::var("SESSION").expect("should have a session token set");
-
-    let client = surf::client().with(Cache(HttpCache {
-        mode: CacheMode::ForceCache,
-        manager: CACacheManager::new(PathBuf::from("cache"), false),
-        options: HttpCacheOptions::default(),
-    }));
-
-    let url = format!("https://adventofcode.com/2025/day/{day}/input");
+/// Number of puzzles this year
+pub const DAYS: u8 = 12;
 
-    let mut response =
-        smol::block_on(client.send(surf::get(url).header("COOKIE", format!("session={session}"))))
-            .map_err(|e| eyre!("{e}"))?;
-
-    let text = smol::block_on(response.body_string()).map_err(|e| eyre!("{e}"))?;
+/// GET a path on adventofcode.com through the shared client, see [`http::Client`]
+pub fn fetch(path: &str, mode: CacheMode) -> Result<String> {
+    http::Client::from_env()?.get(path, mode)
+}
 
-    Ok(text)
+pub fn fetch_input(day: u8) -> Result<String> {
+    http::Client::from_env()?.input(day)
 }
@@ -1,9 +1,411 @@
-use advent_of_code_2025::*;
-use color_eyre::eyre::Result;
+use std::{
+    io::IsTerminal,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use advent_of_code_2025::{
+    bench,
+    cache::{self, Selection},
+    http::Client,
+    input::{Input, InputSource},
+    prefetch, puzzle,
+    registry::{self, Solution},
+    runner,
+    stats::{self, Answers},
+};
+use clap::{Args, Parser, Subcommand};
+use color_eyre::eyre::{Result, eyre};
+
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run solutions, the default when no command is given
+    Run(RunArgs),
+
+    /// Time parsing and each part repeatedly, optionally saving or comparing against a named baseline
+    Bench(BenchArgs),
+
+    /// Print a day's puzzle description
+    Puzzle {
+        /// Day number
+        day: u8,
+
+        /// Download the page again, e.g. once part two is unlocked
+        #[arg(long)]
+        refresh: bool,
+
+        /// Print without colors or emphasis, as when not printing to a terminal
+        #[arg(long)]
+        plain: bool,
+    },
+
+    /// Show a calendar of stars, personal times and recorded answers
+    Stats {
+        /// File with locally recorded answers
+        #[arg(long, default_value = "answers.tsv")]
+        answers: PathBuf,
+    },
+
+    /// Record an answer locally, shown next to the stars by `stats`
+    Record {
+        day: u8,
+        part: u8,
+        answer: String,
+
+        /// File with locally recorded answers
+        #[arg(long, default_value = "answers.tsv")]
+        answers: PathBuf,
+    },
+
+    /// Download every unlocked day's input that isn't cached yet
+    Prefetch {
+        /// Also download the puzzle pages
+        #[arg(long)]
+        puzzles: bool,
+    },
+
+    /// Inspect or purge the HTTP cache
+    #[command(subcommand)]
+    Cache(CacheCommand),
+
+    /// Follow day 1's dial through the rotations
+    #[cfg(feature = "day01")]
+    Dial(DialArgs),
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// List cached responses with their size and when they were stored
+    List,
+
+    /// Print a cached response body
+    Show {
+        /// Path like `/2025/day/1/input`, or the full URL as listed
+        entry: String,
+    },
+
+    /// Remove cached responses for one day, or everything
+    Purge {
+        /// Day number
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u8>,
+
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day number (`8`), solution name (`day01_fast`) or `all`
+    #[arg(default_value = "10")]
+    day: String,
+
+    /// Input to run on: `fetch`, `-` for stdin, `sample`, `sample:<name>` or a file path
+    #[arg(short, long, default_value = "fetch")]
+    input: InputSource,
+
+    /// Puzzle parameter as `key=value`, e.g. `connections=10` for day 8
+    #[arg(short, long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Give up on a solution after this many seconds
+    #[arg(short, long, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// List all solutions and whether they were compiled in
+    #[arg(long)]
+    list: bool,
+
+    /// Print a JSON record per part with its answer, timings and status, one per line
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Day number (`8`), solution name (`day01_fast`) or `all`
+    #[arg(default_value = "all")]
+    day: String,
+
+    /// Input to run on: `fetch`, `-` for stdin, `sample`, `sample:<name>` or a file path
+    #[arg(short, long, default_value = "fetch")]
+    input: InputSource,
+
+    /// Puzzle parameter as `key=value`, e.g. `connections=10` for day 8
+    #[arg(short, long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Timed runs per phase
+    #[arg(short, long, default_value_t = 20)]
+    runs: usize,
+
+    /// Save the results as a baseline with this name
+    #[arg(long)]
+    save: Option<String>,
+
+    /// Compare the results against the baseline with this name
+    #[arg(long)]
+    baseline: Option<String>,
+
+    /// Slowdown in percent that fails the comparison, if statistically significant
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+}
+
+#[cfg(feature = "day01")]
+#[derive(Args)]
+struct DialArgs {
+    /// Input to run on: `fetch`, `-` for stdin, `sample`, `sample:<name>` or a file path
+    #[arg(short, long, default_value = "fetch")]
+    input: InputSource,
+
+    /// Parameter as `key=value`: `size`, `start` or `target` of the dial, or `skip_blank=true`
+    #[arg(short, long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Print what each rotation did as a table
+    #[arg(long, conflicts_with_all = ["histogram", "animate"])]
+    trace: bool,
+
+    /// Print how often every position was stopped at and pointed at, as CSV
+    #[arg(long, conflicts_with = "animate")]
+    histogram: bool,
+
+    /// Draw the dial and turn it through the rotations
+    #[arg(long)]
+    animate: bool,
+
+    /// Clicks a second when animating
+    #[arg(long, default_value_t = 200.0, requires = "animate")]
+    speed: f64,
+}
+
+fn parse_param(s: &str) -> Result<(String, String)> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| eyre!("Expected key=value, got {s:?}"))?;
+
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+fn parse_seconds(s: &str) -> Result<Duration> {
+    Ok(Duration::try_from_secs_f64(s.parse()?)?)
+}
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    day10::main()?;
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Run(cli.run)) {
+        Command::Run(args) => run(args),
+        Command::Bench(args) => bench(args),
+        Command::Puzzle {
+            day,
+            refresh,
+            plain,
+        } => {
+            let ansi = !plain && std::io::stdout().is_terminal();
+            print!("{}", puzzle::show(day, refresh, ansi)?);
+            Ok(())
+        }
+        Command::Stats { answers } => {
+            let progress = stats::merge(&stats::fetch_personal_stats()?, &Answers::load(answers)?);
+            print!("{}", stats::render_calendar(&progress));
+            Ok(())
+        }
+        Command::Record {
+            day,
+            part,
+            answer,
+            answers: path,
+        } => {
+            let mut answers = Answers::load(&path)?;
+            answers.record(day, part, answer);
+            answers.save(&path)
+        }
+        Command::Prefetch { puzzles } => {
+            let days = prefetch::unlocked_days(SystemTime::now());
+            let reports = prefetch::prefetch(&Client::from_env()?, &days, puzzles);
+            print!("{}", prefetch::render(&reports));
+
+            let failures = reports.iter().filter(|r| !r.is_success()).count();
+            if failures > 0 {
+                return Err(eyre!("{failures} day(s) failed to download"));
+            }
+            Ok(())
+        }
+        Command::Cache(command) => cache_command(command),
+        #[cfg(feature = "day01")]
+        Command::Dial(args) => dial(args),
+    }
+}
+
+#[cfg(feature = "day01")]
+fn dial(args: DialArgs) -> Result<()> {
+    use advent_of_code_2025::{day01, day01_animation};
+
+    let mut input = Input::new(args.input);
+    input.params.extend(args.params);
+
+    let dial = day01::dial(&input)?;
+    let text = input.read(1, day01::SAMPLES)?;
+    let rotations = day01::parse_with(&text, day01::skip_blank(&input)?)?;
+
+    if args.trace {
+        print!("{}", day01::render_trace(dial.trace(&rotations)));
+    } else if args.animate {
+        let ansi = std::io::stdout().is_terminal();
+        day01_animation::play(dial, &rotations, args.speed, ansi, &mut std::io::stdout())?;
+    } else if args.histogram {
+        print!("{}", dial.histogram(&rotations).to_csv());
+    } else {
+        println!(
+            "Stopped at {} {} times in {} rotations, pointed at it {} times in all",
+            dial.target,
+            dial.count_landings(&rotations),
+            rotations.len(),
+            dial.count_passes(&rotations)
+        );
+    }
+
+    Ok(())
+}
+
+fn cache_command(command: CacheCommand) -> Result<()> {
+    let client = Client::from_env()?;
+
+    match command {
+        CacheCommand::List => print!("{}", cache::render(&cache::list(&client)?)),
+        CacheCommand::Show { entry } => {
+            let path = entry.strip_prefix(&client.base_url).unwrap_or(&entry);
+            let (status, body) = client.cached(path)?;
+
+            if !status.is_success() {
+                eprintln!("Cached with status {status}");
+            }
+            print!("{body}");
+        }
+        CacheCommand::Purge { day, all: _ } => {
+            let selection = day.map_or(Selection::All, Selection::Day);
+
+            for entry in cache::purge(&client, selection)? {
+                eprintln!("Removed {}", entry.url);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Solutions to run for a day number, solution name or `all`
+fn select(day: &str) -> Result<Vec<&'static Solution>> {
+    match day {
+        "all" => Ok(registry::SOLUTIONS.iter().collect()),
+        day => Ok(vec![
+            registry::find(day).ok_or_else(|| eyre!("No solution for day {day:?}"))?,
+        ]),
+    }
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    if args.list {
+        for solution in registry::SOLUTIONS {
+            let status = match solution.is_enabled() {
+                true => "enabled".to_owned(),
+                false => format!("disabled (feature `{}`)", solution.feature),
+            };
+            println!("{:<12} {status}", solution.name);
+        }
+
+        return Ok(());
+    }
+
+    let solutions = select(&args.day)?;
+
+    let mut input = Input::new(args.input);
+    input.params.extend(args.params);
+
+    let mut texts = runner::Texts::new(solutions.len());
+    let mut failures = 0;
+
+    for solution in solutions {
+        let run = runner::run_phases_isolated(solution, input.clone(), &mut texts, args.timeout);
+
+        if args.json {
+            // One record per part, each on its own line
+            for record in run.records(solution, &input.source) {
+                println!("{}", serde_json::to_string(&record)?);
+            }
+        } else {
+            println!("{:<12} {run}", solution.name);
+        }
+
+        if !run.is_success() {
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        return Err(eyre!("{failures} run(s) failed"));
+    }
+
+    Ok(())
+}
+
+fn bench(args: BenchArgs) -> Result<()> {
+    let mut input = Input::new(args.input);
+    input.params.extend(args.params);
+
+    let solutions = select(&args.day)?;
+    let mut texts = runner::Texts::new(solutions.len());
+    let mut measurements = Vec::new();
+
+    for solution in solutions {
+        // Skip what's compiled out unless it was asked for by name
+        if !solution.is_enabled() && args.day == "all" {
+            continue;
+        }
+
+        eprintln!("Timing {}", solution.name);
+        measurements.extend(bench::measure(solution, &input, &mut texts, args.runs)?);
+    }
+
+    if let Some(name) = &args.save {
+        bench::save_baseline(name, &measurements)?;
+        eprintln!("Saved baseline {}", bench::baseline_path(name).display());
+    }
+
+    let Some(name) = &args.baseline else {
+        print!("{}", bench::render_measurements(&measurements));
+        return Ok(());
+    };
+
+    let threshold = args.threshold / 100.0;
+    let comparisons = bench::compare(&measurements, &bench::load_baseline(name)?)?;
+    print!("{}", bench::render_comparisons(&comparisons, threshold));
+
+    let regressions = comparisons
+        .iter()
+        .filter(|c| c.verdict(threshold) == bench::Verdict::Regressed)
+        .count();
+
+    if regressions > 0 {
+        return Err(eyre!(
+            "{regressions} phase(s) regressed by more than {}%",
+            args.threshold
+        ));
+    }
 
     Ok(())
 }
@@ -0,0 +1,34 @@
+use std::{
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rand::{SeedableRng, rngs::StdRng};
+
+/// Random cases per property, overridable with `ORACLE_CASES`
+const DEFAULT_CASES: u64 = 200;
+
+/// Check `property` on random inputs from `generate`, seeding each case separately
+///
+/// A failing case prints its seed and input, set `ORACLE_SEED` to replay just that case.
+pub fn check<T: Debug>(generate: impl Fn(&mut StdRng) -> T, property: impl Fn(&T)) {
+    let seeds = match std::env::var("ORACLE_SEED") {
+        Ok(seed) => vec![seed.parse().expect("ORACLE_SEED should be a number")],
+        Err(_) => {
+            let cases = std::env::var("ORACLE_CASES")
+                .map(|cases| cases.parse().expect("ORACLE_CASES should be a number"))
+                .unwrap_or(DEFAULT_CASES);
+
+            (0..cases).collect()
+        }
+    };
+
+    for seed in seeds {
+        let input = generate(&mut StdRng::seed_from_u64(seed));
+
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| property(&input))) {
+            eprintln!("Failed with ORACLE_SEED={seed} on {input:?}");
+            panic::resume_unwind(payload);
+        }
+    }
+}
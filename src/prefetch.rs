@@ -0,0 +1,205 @@
+use std::{
+    fmt::Write,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::Result;
+use http_cache_surf::CacheMode;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{
+    DAYS, YEAR,
+    http::{Client, input_path},
+    puzzle::puzzle_path,
+};
+
+/// Puzzles unlock at midnight US Eastern time
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// Days since the Unix epoch of a date, from Howard Hinnant's `days_from_civil`
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// When a day's puzzle unlocks, in December of [`YEAR`]
+pub fn unlock_time(day: u8) -> SystemTime {
+    let days = days_from_civil(YEAR.into(), 12, day.into()) as u64;
+    UNIX_EPOCH + Duration::from_secs(days * 86400 + UNLOCK_HOUR_UTC * 3600)
+}
+
+/// Days whose puzzles are unlocked at `now`
+pub fn unlocked_days(now: SystemTime) -> Vec<u8> {
+    (1..=DAYS).filter(|&day| unlock_time(day) <= now).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Fetched,
+    Cached,
+    Failed(String),
+}
+
+/// What happened to one day's downloads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub day: u8,
+    pub input: Status,
+    /// Only when puzzle pages were asked for
+    pub puzzle: Option<Status>,
+}
+
+impl Report {
+    pub fn is_success(&self) -> bool {
+        [Some(&self.input), self.puzzle.as_ref()]
+            .into_iter()
+            .flatten()
+            .all(|status| !matches!(status, Status::Failed(_)))
+    }
+}
+
+/// Download `path` with `fetch` unless it's already cached
+fn fetch_missing(
+    client: &Client,
+    path: &str,
+    fetch: impl FnOnce(&Client) -> Result<String>,
+) -> Status {
+    if client.is_cached(path) {
+        return Status::Cached;
+    }
+
+    match fetch(client) {
+        Ok(_) => Status::Fetched,
+        Err(e) => Status::Failed(format!("{e:#}")),
+    }
+}
+
+/// Fetch the missing inputs of `days`, and their puzzle pages with `puzzles`
+///
+/// Goes through the client's throttling, and shows progress on stderr when it's a terminal.
+pub fn prefetch(client: &Client, days: &[u8], puzzles: bool) -> Vec<Report> {
+    let per_day = if puzzles { 2 } else { 1 };
+    let progress = ProgressBar::new((days.len() * per_day) as u64).with_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {msg}")
+            .expect("progress template should be valid"),
+    );
+
+    let reports = days
+        .iter()
+        .map(|&day| {
+            progress.set_message(format!("day {day} input"));
+            // Through `Client::input` so a missing session fails here rather than at the site
+            let input = fetch_missing(client, &input_path(day), |client| client.input(day));
+            progress.inc(1);
+
+            let puzzle = puzzles.then(|| {
+                progress.set_message(format!("day {day} puzzle"));
+                let path = puzzle_path(day);
+                let status = fetch_missing(client, &path, |client| {
+                    client.get(&path, CacheMode::ForceCache)
+                });
+                progress.inc(1);
+                status
+            });
+
+            Report { day, input, puzzle }
+        })
+        .collect();
+
+    progress.finish_and_clear();
+    reports
+}
+
+fn describe(status: &Status) -> &str {
+    match status {
+        Status::Fetched => "fetched",
+        Status::Cached => "cached",
+        Status::Failed(_) => "FAILED",
+    }
+}
+
+/// One line per day, with the reasons for any failures
+pub fn render(reports: &[Report]) -> String {
+    let mut out = String::new();
+
+    for report in reports {
+        write!(
+            out,
+            "Day {:>2}  input {:<8}",
+            report.day,
+            describe(&report.input)
+        )
+        .unwrap();
+        if let Some(puzzle) = &report.puzzle {
+            write!(out, "  puzzle {:<8}", describe(puzzle)).unwrap();
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+
+        for (what, status) in [
+            ("input", Some(&report.input)),
+            ("puzzle", report.puzzle.as_ref()),
+        ] {
+            if let Some(Status::Failed(reason)) = status {
+                writeln!(out, "        {what}: {reason}").unwrap();
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_time() {
+        let first = UNIX_EPOCH + Duration::from_secs(1764565200);
+        assert_eq!(unlock_time(1), first);
+        assert_eq!(
+            unlock_time(12),
+            first + Duration::from_secs(11 * 24 * 60 * 60)
+        );
+
+        assert!(unlocked_days(first - Duration::from_secs(1)).is_empty());
+        assert_eq!(unlocked_days(first), [1]);
+        assert_eq!(
+            unlocked_days(first + Duration::from_secs(2 * 24 * 60 * 60 - 1)),
+            [1, 2]
+        );
+        assert_eq!(
+            unlocked_days(first + Duration::from_secs(365 * 24 * 60 * 60)),
+            (1..=DAYS).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let reports = [
+            Report {
+                day: 1,
+                input: Status::Cached,
+                puzzle: Some(Status::Fetched),
+            },
+            Report {
+                day: 2,
+                input: Status::Failed("404 Not Found".to_owned()),
+                puzzle: Some(Status::Cached),
+            },
+        ];
+
+        assert_eq!(
+            render(&reports),
+            "Day  1  input cached    puzzle fetched\n\
+             Day  2  input FAILED    puzzle cached\n        input: 404 Not Found\n"
+        );
+        assert!(reports[0].is_success());
+        assert!(!reports[1].is_success());
+    }
+}
@@ -0,0 +1,390 @@
+use color_eyre::eyre::{Result, bail};
+use http_cache_surf::CacheMode;
+
+use crate::{YEAR, fetch};
+
+const WIDTH: usize = 80;
+
+pub fn puzzle_path(day: u8) -> String {
+    format!("/{YEAR}/day/{day}")
+}
+
+/// Fetch a day's puzzle page, reloading it with `refresh` (e.g. once part two unlocks)
+pub fn fetch_puzzle(day: u8, refresh: bool) -> Result<String> {
+    let mode = match refresh {
+        true => CacheMode::Reload,
+        false => CacheMode::ForceCache,
+    };
+
+    fetch(&puzzle_path(day), mode)
+}
+
+/// Inner HTML of each `<article>` on the page, part one first
+pub fn articles(html: &str) -> Vec<&str> {
+    let mut articles = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find("<article") {
+        let Some(open_end) = rest[start..].find('>') else {
+            break;
+        };
+        let body_start = start + open_end + 1;
+
+        let Some(len) = rest[body_start..].find("</article>") else {
+            break;
+        };
+
+        articles.push(&rest[body_start..body_start + len]);
+        rest = &rest[body_start + len..];
+    }
+
+    articles
+}
+
+/// Fetch and render a day's puzzle description
+pub fn show(day: u8, refresh: bool, ansi: bool) -> Result<String> {
+    let html = fetch_puzzle(day, refresh)?;
+
+    if articles(&html).is_empty() {
+        bail!("Day {day} has no puzzle description, is it unlocked yet?");
+    }
+
+    Ok(render(&html, ansi))
+}
+
+/// Render every article on a puzzle page as wrapped terminal text
+///
+/// With `ansi`, emphasis is bold and code is cyan.
+pub fn render(html: &str, ansi: bool) -> String {
+    let mut renderer = Renderer::new(ansi);
+
+    for article in articles(html) {
+        renderer.render(article);
+    }
+
+    renderer.out.trim_end().to_owned() + "\n"
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Open(&'a str),
+    Close(&'a str),
+}
+
+fn tokenize(html: &str) -> impl Iterator<Item = Token<'_>> {
+    let mut rest = html;
+
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        // A tag starts with a name, `/` or `!`, anything else after `<` is just text
+        let tag = rest
+            .strip_prefix('<')
+            .and_then(|after| after.split_once('>'))
+            .filter(|(tag, _)| {
+                tag.starts_with(|c: char| c.is_ascii_alphabetic() || "/!".contains(c))
+            });
+        if let Some((tag, tail)) = tag {
+            rest = tail;
+            return Some(match tag.strip_prefix('/') {
+                Some(tag) => Token::Close(tag_name(tag)),
+                None => Token::Open(tag_name(tag)),
+            });
+        }
+
+        // Up to the next `<`, past one at the start that didn't open a tag
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == '<')
+            .map_or(rest.len(), |(i, _)| i);
+        let (text, tail) = rest.split_at(end);
+        rest = tail;
+        Some(Token::Text(text))
+    })
+}
+
+/// `a` from `a href="..."`, `br` from `br/`
+fn tag_name(tag: &str) -> &str {
+    tag.split([' ', '/']).find(|s| !s.is_empty()).unwrap_or("")
+}
+
+/// Text of an HTML fragment with the tags removed and entities decoded
+pub(crate) fn strip_tags(html: &str) -> String {
+    tokenize(html)
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(decode_entities(text)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Width of `text` on screen, skipping ANSI escape sequences
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => width += 1,
+        }
+    }
+
+    width
+}
+
+struct Renderer {
+    ansi: bool,
+    out: String,
+    /// Inline content of the current paragraph, wrapped when flushed
+    paragraph: String,
+    /// Prefix for the first line of the current paragraph, e.g. a list bullet
+    bullet: &'static str,
+    /// Content of the current `<pre>` block, `None` outside of one
+    pre: Option<String>,
+    emphasis: usize,
+    code: usize,
+}
+
+impl Renderer {
+    fn new(ansi: bool) -> Self {
+        Self {
+            ansi,
+            out: String::new(),
+            paragraph: String::new(),
+            bullet: "",
+            pre: None,
+            emphasis: 0,
+            code: 0,
+        }
+    }
+
+    fn render(&mut self, article: &str) {
+        for token in tokenize(article) {
+            match token {
+                Token::Text(text) => self.text(&decode_entities(text)),
+                Token::Open(tag) => self.open(tag),
+                Token::Close(tag) => self.close(tag),
+            }
+        }
+
+        self.flush();
+    }
+
+    fn open(&mut self, tag: &str) {
+        match tag {
+            "h2" => {
+                self.flush();
+                self.emphasis += 1;
+                self.restyle();
+            }
+            "p" | "ul" => self.flush(),
+            "li" => {
+                self.flush();
+                self.bullet = "  - ";
+            }
+            "pre" => {
+                self.flush();
+                self.pre = Some(String::new());
+            }
+            "em" => {
+                self.emphasis += 1;
+                self.restyle();
+            }
+            "code" => {
+                self.code += 1;
+                self.restyle();
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, tag: &str) {
+        match tag {
+            "h2" => {
+                self.emphasis = self.emphasis.saturating_sub(1);
+                self.restyle();
+                self.flush();
+            }
+            "p" | "li" | "ul" => self.flush(),
+            "pre" => self.flush_pre(),
+            "em" => {
+                self.emphasis = self.emphasis.saturating_sub(1);
+                self.restyle();
+            }
+            "code" => {
+                self.code = self.code.saturating_sub(1);
+                self.restyle();
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(pre) = &mut self.pre {
+            pre.push_str(text);
+            return;
+        }
+
+        // Collapse whitespace like a browser would
+        for c in text.chars() {
+            if !c.is_ascii_whitespace() {
+                self.paragraph.push(c);
+            } else if !self.paragraph.is_empty() && !self.paragraph.ends_with(' ') {
+                self.paragraph.push(' ');
+            }
+        }
+    }
+
+    /// Emit the escape sequence for the current style
+    fn restyle(&mut self) {
+        if !self.ansi {
+            return;
+        }
+
+        let mut escape = String::from("\x1b[0m");
+        if self.emphasis > 0 {
+            escape.push_str("\x1b[1m");
+        }
+        if self.code > 0 {
+            escape.push_str("\x1b[36m");
+        }
+
+        match &mut self.pre {
+            Some(pre) => pre.push_str(&escape),
+            None => self.paragraph.push_str(&escape),
+        }
+    }
+
+    /// Indent the finished `<pre>` block into the output, followed by a blank line
+    fn flush_pre(&mut self) {
+        let Some(pre) = self.pre.take() else {
+            return;
+        };
+
+        let mut lines = pre.split('\n').map(str::to_owned).collect::<Vec<_>>();
+
+        // Drop the trailing newline, keeping any style changes that came after it
+        while lines.len() > 1 && visible_width(lines.last().unwrap()) == 0 {
+            let last = lines.pop().unwrap();
+            lines.last_mut().unwrap().push_str(&last);
+        }
+
+        for line in lines {
+            self.out.push_str("    ");
+            self.out.push_str(&line);
+            self.out.push('\n');
+        }
+
+        self.out.push('\n');
+    }
+
+    /// Word wrap the pending paragraph into the output, followed by a blank line
+    fn flush(&mut self) {
+        let paragraph = std::mem::take(&mut self.paragraph);
+        let bullet = std::mem::take(&mut self.bullet);
+
+        if visible_width(&paragraph) == 0 {
+            // Keep any trailing style changes so they still apply
+            self.out.push_str(&paragraph);
+            return;
+        }
+
+        let indent = " ".repeat(bullet.len());
+        let mut line = bullet.to_owned();
+        let mut line_width = bullet.len();
+
+        for word in paragraph.split(' ').filter(|w| !w.is_empty()) {
+            let word_width = visible_width(word);
+
+            if line_width > indent.len() && line_width + 1 + word_width > WIDTH {
+                self.out.push_str(line.trim_end());
+                self.out.push('\n');
+                line = indent.clone();
+                line_width = indent.len();
+            }
+
+            if line_width > indent.len() && word_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+
+            line.push_str(word);
+            line_width += word_width;
+        }
+
+        self.out.push_str(line.trim_end());
+        self.out.push_str("\n\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PART_ONE: &str = include_str!("../fixtures/puzzle_part_one.html");
+    const BOTH_PARTS: &str = include_str!("../fixtures/puzzle_both_parts.html");
+
+    #[test]
+    fn test_articles() {
+        assert_eq!(articles(PART_ONE).len(), 1);
+        assert_eq!(articles(BOTH_PARTS).len(), 2);
+        assert_eq!(articles("<html><main></main></html>").len(), 0);
+    }
+
+    #[test]
+    fn test_stray_angle_brackets() {
+        assert_eq!(strip_tags("x <"), "x <");
+        assert_eq!(strip_tags("<é"), "<é");
+        assert_eq!(strip_tags("a < b <b>c</b> d<3 <>"), "a < b c d<3 <>");
+        assert_eq!(strip_tags("<p>cut off <em"), "cut off <em");
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let text = render(PART_ONE, false);
+
+        assert!(text.starts_with("--- Day 1: Sample Puzzle ---\n\n"));
+        assert!(text.contains("\n    L68\n    L30\n    R48\n"));
+        assert!(text.contains("  - The dial starts by pointing at 50."));
+        assert!(text.contains("the password is 3 & not <4>."));
+        assert!(text.lines().all(|line| line.chars().count() <= WIDTH));
+        assert!(!text.contains("--- Part Two ---"));
+    }
+
+    #[test]
+    fn test_render_both_parts() {
+        let text = render(BOTH_PARTS, false);
+
+        let part_one = text.find("--- Day 1: Sample Puzzle ---").unwrap();
+        let part_two = text.find("--- Part Two ---").unwrap();
+        assert!(part_one < part_two);
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let text = render(PART_ONE, true);
+
+        assert!(text.contains("\x1b[0m\x1b[1m3\x1b[0m"));
+        assert!(text.contains("\x1b[0m\x1b[36mL68"));
+        assert_eq!(
+            visible_width(&text),
+            visible_width(&render(PART_ONE, false))
+        );
+    }
+}
@@ -0,0 +1,281 @@
+use std::{any::Any, fmt, io::Read};
+
+use color_eyre::eyre::{Report, Result, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::input::Input;
+
+/// A day's parsed input, type-erased so every day fits in one table
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+/// Parse the input once, for both parts to share, with the input for its parameters
+#[derive(Clone, Copy)]
+pub enum Parse {
+    /// From the whole text, read before parsing starts
+    Text(fn(&str, &Input) -> Result<Parsed>),
+    /// Reading as it goes, straight from the file or stdin when nothing else needs the text
+    Stream(fn(&mut dyn Read, &Input) -> Result<Parsed>),
+}
+
+impl Parse {
+    /// Parse `text` that's already been read, whichever way the solution takes its input
+    pub fn text(&self, text: &str, input: &Input) -> Result<Parsed> {
+        match self {
+            Self::Text(parse) => parse(text, input),
+            Self::Stream(parse) => parse(&mut text.as_bytes(), input),
+        }
+    }
+}
+
+/// One part of a solution, solving the parsed input to a printable answer
+pub type Part = fn(&Parsed, &Input) -> Result<String>;
+
+/// Parsing or one of the parts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    pub const ALL: [Self; 3] = [Self::Parse, Self::Part1, Self::Part2];
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parse"),
+            Self::Part1 => write!(f, "part 1"),
+            Self::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+/// A solution split up so parsing and each part can be timed on their own
+#[derive(Clone, Copy)]
+pub struct Phases {
+    pub parse: Parse,
+    pub parts: [Part; 2],
+}
+
+/// The value a solution's own [`Parse`] produced
+///
+/// Panics if it's some other type, which would be a mistake in [`SOLUTIONS`].
+pub fn downcast<T: Any>(parsed: &Parsed) -> &T {
+    parsed
+        .downcast_ref()
+        .expect("parts should be given the input their own solution parsed")
+}
+
+/// A solution for one day, which may be compiled out by its cargo feature
+pub struct Solution {
+    pub day: u8,
+    /// Module name, e.g. `day01_fast`
+    pub name: &'static str,
+    /// Cargo feature the module is gated behind
+    pub feature: &'static str,
+    /// Examples from the puzzle text, empty when the feature is disabled
+    pub samples: &'static [(&'static str, &'static str)],
+    /// Parsing and both parts on their own, for timing them separately, `None` when the
+    /// feature is disabled
+    pub phases: Option<Phases>,
+}
+
+impl Solution {
+    pub fn is_enabled(&self) -> bool {
+        self.phases.is_some()
+    }
+
+    fn disabled(&self) -> Report {
+        eyre!(
+            "{} is disabled, rebuild with `--features {}`",
+            self.name,
+            self.feature
+        )
+    }
+
+    pub fn phases(&self) -> Result<Phases> {
+        self.phases.ok_or_else(|| self.disabled())
+    }
+
+    /// Read the input text for this solution's day
+    pub fn read(&self, input: &Input) -> Result<String> {
+        input.read(self.day, self.samples)
+    }
+
+    /// Open the input for this solution's day to read as it goes
+    pub fn reader(&self, input: &Input) -> Result<Box<dyn Read>> {
+        input.reader(self.day, self.samples)
+    }
+}
+
+/// Parse with the module's `parse`, checking it produces the module's `Parsed`
+// Unused when only days with custom parsing are enabled
+#[allow(unused_macros)]
+macro_rules! parse {
+    ($name:ident) => {
+        Parse::Text(|text, _| {
+            let parsed: crate::$name::Parsed = crate::$name::parse(text)?;
+            Ok(Box::new(parsed))
+        })
+    };
+}
+
+macro_rules! solution {
+    ($day:literal, $name:ident, $feature:tt) => {
+        solution!(
+            $day,
+            $name,
+            $feature,
+            Phases {
+                parse: parse!($name),
+                parts: [
+                    |parsed, _| {
+                        let parsed = downcast::<crate::$name::Parsed>(parsed);
+                        Ok(crate::$name::part_1(parsed)?.to_string())
+                    },
+                    |parsed, _| {
+                        let parsed = downcast::<crate::$name::Parsed>(parsed);
+                        Ok(crate::$name::part_2(parsed)?.to_string())
+                    },
+                ],
+            }
+        )
+    };
+    ($day:literal, $name:ident, $feature:tt, $phases:expr) => {
+        Solution {
+            day: $day,
+            name: stringify!($name),
+            feature: $feature,
+            #[cfg(feature = $feature)]
+            samples: crate::$name::SAMPLES,
+            #[cfg(not(feature = $feature))]
+            samples: &[],
+            #[cfg(feature = $feature)]
+            phases: Some($phases),
+            #[cfg(not(feature = $feature))]
+            phases: None,
+        }
+    };
+}
+
+pub const SOLUTIONS: &[Solution] = &[
+    solution!(
+        1,
+        day01,
+        "day01",
+        Phases {
+            parse: Parse::Text(|text, input| {
+                let skip_blank = crate::day01::skip_blank(input)?;
+                let parsed: crate::day01::Parsed = crate::day01::parse_with(text, skip_blank)?;
+                Ok(Box::new(parsed))
+            }),
+            parts: [
+                |parsed, input| {
+                    let rotations = downcast::<crate::day01::Parsed>(parsed);
+                    Ok(crate::day01::dial(input)?
+                        .count_landings(rotations)
+                        .to_string())
+                },
+                |parsed, input| {
+                    let rotations = downcast::<crate::day01::Parsed>(parsed);
+                    Ok(crate::day01::dial(input)?
+                        .count_passes(rotations)
+                        .to_string())
+                },
+            ],
+        }
+    ),
+    // Parses as it goes, so both answers come out of parsing
+    solution!(
+        1,
+        day01_fast,
+        "day01",
+        Phases {
+            parse: Parse::Text(|text, input| {
+                let (dial, skip_blank) =
+                    (crate::day01::dial(input)?, crate::day01::skip_blank(input)?);
+                let answers = crate::day01_fast::solve(text.as_bytes(), dial, skip_blank)?;
+                Ok(Box::new(answers))
+            }),
+            parts: [
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).0.to_string()),
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).1.to_string()),
+            ],
+        }
+    ),
+    // Parses as it goes too, just on every thread at once
+    solution!(
+        1,
+        day01_parallel,
+        "day01",
+        Phases {
+            parse: Parse::Text(|text, input| {
+                let (dial, skip_blank) =
+                    (crate::day01::dial(input)?, crate::day01::skip_blank(input)?);
+                let answers = crate::day01_parallel::solve(text.as_bytes(), dial, skip_blank)?;
+                Ok(Box::new(answers))
+            }),
+            parts: [
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).0.to_string()),
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).1.to_string()),
+            ],
+        }
+    ),
+    // Solves while it reads, so both answers come out of parsing
+    solution!(
+        1,
+        day01_stream,
+        "day01",
+        Phases {
+            parse: Parse::Stream(|reader, input| {
+                let (dial, skip_blank) =
+                    (crate::day01::dial(input)?, crate::day01::skip_blank(input)?);
+                let answers = crate::day01_stream::solve(reader, dial, skip_blank)?;
+                Ok(Box::new(answers))
+            }),
+            parts: [
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).0.to_string()),
+                |answers, _| Ok(downcast::<(u64, u64)>(answers).1.to_string()),
+            ],
+        }
+    ),
+    solution!(2, day02, "day02"),
+    solution!(2, day02_fast, "day02"),
+    solution!(3, day03, "day03"),
+    solution!(4, day04, "day04"),
+    solution!(5, day05, "day05"),
+    solution!(6, day06, "day06"),
+    solution!(7, day07, "day07"),
+    solution!(
+        8,
+        day08,
+        "day08",
+        Phases {
+            parse: parse!(day08),
+            parts: [
+                |parsed, input| {
+                    let points = downcast::<crate::day08::Parsed>(parsed);
+                    let connections = crate::day08::connections(points, input)?;
+                    Ok(crate::day08::part_1(points, connections)?.to_string())
+                },
+                |parsed, _| {
+                    let points = downcast::<crate::day08::Parsed>(parsed);
+                    Ok(crate::day08::part_2(points)?.to_string())
+                },
+            ],
+        }
+    ),
+    solution!(9, day09, "day09"),
+    solution!(10, day10, "day10"),
+];
+
+/// Look up a solution by module name (`day01_fast`) or day number (`1`)
+pub fn find(name: &str) -> Option<&'static Solution> {
+    match name.parse::<u8>() {
+        Ok(day) => SOLUTIONS.iter().find(|s| s.day == day),
+        Err(_) => SOLUTIONS.iter().find(|s| s.name == name),
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{
+    Report,
+    eyre::{Result, eyre},
+};
+
+use serde::Serialize;
+
+use crate::{
+    input::{Input, InputSource},
+    registry::{Parse, Solution},
+};
+
+/// How running one solution went
+#[derive(Debug)]
+pub enum Outcome {
+    Finished(Duration),
+    Failed(Report),
+    Panicked(String),
+    TimedOut(Duration),
+    /// Compiled out by its cargo feature
+    Disabled(&'static str),
+}
+
+impl Outcome {
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Finished(_) | Self::Disabled(_))
+    }
+
+    /// Short machine-readable name of the variant
+    pub fn status(&self) -> &'static str {
+        match self {
+            Self::Finished(_) => "ok",
+            Self::Failed(_) => "failed",
+            Self::Panicked(_) => "panicked",
+            Self::TimedOut(_) => "timed_out",
+            Self::Disabled(_) => "disabled",
+        }
+    }
+
+    /// What went wrong, `None` when it finished
+    pub fn error(&self) -> Option<String> {
+        match self {
+            Self::Finished(_) => None,
+            Self::Failed(report) => Some(format!("{report:#}")),
+            Self::Panicked(message) => Some(message.clone()),
+            Self::TimedOut(limit) => Some(format!("timed out after {limit:?}")),
+            Self::Disabled(feature) => Some(format!("rebuild with `--features {feature}`")),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Finished(elapsed) => write!(f, "ok in {elapsed:?}"),
+            Self::Failed(report) => write!(f, "failed: {report:#}"),
+            Self::Panicked(message) => write!(f, "panicked: {message}"),
+            Self::TimedOut(limit) => write!(f, "timed out after {limit:?}"),
+            Self::Disabled(feature) => write!(f, "disabled (feature `{feature}`)"),
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_owned()
+    }
+}
+
+/// Run `work` on a worker thread named `name`, catching panics and giving up after `timeout`
+///
+/// A timed out worker can't be cancelled, it keeps running detached until the process exits.
+fn isolate<T: Send + 'static>(
+    name: &str,
+    timeout: Option<Duration>,
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<(T, Duration), Outcome> {
+    let (sender, receiver) = mpsc::channel();
+
+    let spawned = thread::Builder::new().name(name.to_owned()).spawn(move || {
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(work));
+
+        let result = match result {
+            Ok(Ok(value)) => Ok((value, start.elapsed())),
+            Ok(Err(report)) => Err(Outcome::Failed(report)),
+            Err(payload) => Err(Outcome::Panicked(panic_message(payload))),
+        };
+
+        // The runner may have stopped waiting already
+        let _ = sender.send(result);
+    });
+
+    if let Err(e) = spawned {
+        return Err(Outcome::Failed(e.into()));
+    }
+
+    let received = match timeout {
+        Some(limit) => receiver.recv_timeout(limit),
+        None => receiver.recv().map_err(RecvTimeoutError::from),
+    };
+
+    match received {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(Outcome::TimedOut(timeout.unwrap_or_default())),
+        Err(RecvTimeoutError::Disconnected) => Err(Outcome::Panicked(
+            "worker exited without a result".to_owned(),
+        )),
+    }
+}
+
+/// Input texts read once and shared by every solution run on them
+///
+/// Stdin can only be read once, and each day's input only needs fetching once.
+#[derive(Debug)]
+pub struct Texts {
+    /// Whether more than one solution runs, so even those that stream read the shared text
+    shared: bool,
+    read: HashMap<Option<u8>, Arc<String>>,
+}
+
+impl Texts {
+    /// For running `solutions` solutions on the same input
+    pub fn new(solutions: usize) -> Self {
+        Self {
+            shared: solutions > 1,
+            read: HashMap::new(),
+        }
+    }
+
+    /// The input text for `solution`, read on a worker thread the first time so a slow download
+    /// or a stdin nothing writes to gives up after `timeout` like the phases do
+    pub fn get(
+        &mut self,
+        solution: &'static Solution,
+        input: &Input,
+        timeout: Option<Duration>,
+    ) -> Result<Arc<String>, Outcome> {
+        // Files and stdin are the same text whatever the day
+        let day = matches!(input.source, InputSource::Fetch | InputSource::Sample(_))
+            .then_some(solution.day);
+        if let Some(text) = self.read.get(&day) {
+            return Ok(text.clone());
+        }
+
+        let name = format!("{}-read", solution.name);
+        let input = input.clone();
+        let (text, _) = isolate(&name, timeout, move || solution.read(&input))?;
+
+        let text = Arc::new(text);
+        self.read.insert(day, text.clone());
+        Ok(text)
+    }
+}
+
+/// How running one part went, with its answer if it finished
+#[derive(Debug)]
+pub struct PartOutcome {
+    pub part: u8,
+    pub outcome: Outcome,
+    pub answer: Option<String>,
+}
+
+/// How parsing and then each part of one solution went
+#[derive(Debug)]
+pub struct PhasedRun {
+    pub parse: Outcome,
+    pub parts: Vec<PartOutcome>,
+}
+
+fn nanos(outcome: &Outcome) -> Option<u64> {
+    match outcome {
+        Outcome::Finished(elapsed) => Some(elapsed.as_nanos() as u64),
+        _ => None,
+    }
+}
+
+impl PhasedRun {
+    pub fn is_success(&self) -> bool {
+        self.parse.is_success() && self.parts.iter().all(|part| part.outcome.is_success())
+    }
+
+    /// Time spent in the phases that finished
+    pub fn total(&self) -> Duration {
+        [&self.parse]
+            .into_iter()
+            .chain(self.parts.iter().map(|part| &part.outcome))
+            .filter_map(|outcome| match outcome {
+                Outcome::Finished(elapsed) => Some(*elapsed),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Share of the total time spent parsing, if everything finished
+    pub fn parse_share(&self) -> Option<f64> {
+        let Outcome::Finished(parse) = self.parse else {
+            return None;
+        };
+
+        let total = self.total();
+        (self.is_success() && !total.is_zero()).then(|| parse.as_secs_f64() / total.as_secs_f64())
+    }
+
+    /// One JSON record per part of `solution` run on `input`
+    pub fn records<'a>(&'a self, solution: &'a Solution, input: &InputSource) -> Vec<Record<'a>> {
+        self.parts
+            .iter()
+            .map(|part| Record {
+                solution: solution.name,
+                day: solution.day,
+                part: part.part,
+                input: input.to_string(),
+                status: part.outcome.status(),
+                answer: part.answer.as_deref(),
+                parse_ns: nanos(&self.parse),
+                elapsed_ns: nanos(&part.outcome),
+                error: part.outcome.error(),
+            })
+            .collect()
+    }
+}
+
+/// Timings with each phase's share of the total, then the answers
+impl fmt::Display for PhasedRun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Nothing ran, so there's nothing to break down
+        if let Outcome::Disabled(_) = self.parse {
+            return write!(f, "{}", self.parse);
+        }
+
+        let total = self.total().as_secs_f64();
+        let phase = |f: &mut fmt::Formatter<'_>, name: &str, outcome: &Outcome| match outcome {
+            Outcome::Finished(elapsed) if total > 0.0 => write!(
+                f,
+                "{name} {elapsed:.1?} ({:.0}%)",
+                elapsed.as_secs_f64() / total * 100.0
+            ),
+            outcome => write!(f, "{name} {outcome}"),
+        };
+
+        phase(f, "parse", &self.parse)?;
+        for part in &self.parts {
+            write!(f, "  ")?;
+            phase(f, &format!("part {}", part.part), &part.outcome)?;
+        }
+
+        if self.parse_share().is_some_and(|share| share > 0.5) {
+            write!(f, "  <- mostly parsing")?;
+        }
+
+        for part in &self.parts {
+            if let Some(answer) = &part.answer {
+                write!(f, "\n  part {}: {answer}", part.part)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One part's result as a JSON record, see [`PhasedRun::records`]
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    /// Implementation name, e.g. `day01_fast`
+    pub solution: &'a str,
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    /// `ok`, `failed`, `panicked`, `timed_out` or `disabled`
+    pub status: &'static str,
+    pub answer: Option<&'a str>,
+    /// Time spent parsing the input, shared by both parts
+    pub parse_ns: Option<u64>,
+    pub elapsed_ns: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Read the input from `texts` and parse it once, then run each part on the parsed input,
+/// timing every phase separately on its own worker thread
+///
+/// A solution that streams its input reads it while parsing instead, unless other solutions
+/// share the text.
+pub fn run_phases_isolated(
+    solution: &'static Solution,
+    input: Input,
+    texts: &mut Texts,
+    timeout: Option<Duration>,
+) -> PhasedRun {
+    let skipped = |parse: Outcome, outcome: &dyn Fn() -> Outcome| PhasedRun {
+        parse,
+        parts: (1..=2)
+            .map(|part| PartOutcome {
+                part,
+                outcome: outcome(),
+                answer: None,
+            })
+            .collect(),
+    };
+
+    let Some(phases) = solution.phases else {
+        return skipped(Outcome::Disabled(solution.feature), &|| {
+            Outcome::Disabled(solution.feature)
+        });
+    };
+
+    let input = Arc::new(input);
+    let name = format!("{}-parse", solution.name);
+    let parse_input = input.clone();
+
+    let parsed = match phases.parse {
+        Parse::Stream(parse) if !texts.shared => isolate(&name, timeout, move || {
+            parse(&mut solution.reader(&parse_input)?, &parse_input)
+        }),
+        parse => {
+            let text = match texts.get(solution, &input, timeout) {
+                Ok(text) => text,
+                Err(outcome) => {
+                    // Nothing can run, so every phase reports the same error
+                    let message = outcome.error().unwrap_or_default();
+                    return skipped(outcome, &|| Outcome::Failed(eyre!("{message}")));
+                }
+            };
+
+            isolate(&name, timeout, move || parse.text(&text, &parse_input))
+        }
+    };
+
+    let (parsed, parse) = match parsed {
+        Ok((parsed, elapsed)) => (Arc::new(parsed), Outcome::Finished(elapsed)),
+        Err(outcome) => {
+            return skipped(outcome, &|| Outcome::Failed(eyre!("parsing failed")));
+        }
+    };
+
+    let parts = (1..)
+        .zip(phases.parts)
+        .map(|(part, solve)| {
+            let (parsed, input) = (parsed.clone(), input.clone());
+            let name = format!("{}-part{part}", solution.name);
+
+            match isolate(&name, timeout, move || solve(&parsed, &input)) {
+                Ok((answer, elapsed)) => PartOutcome {
+                    part,
+                    outcome: Outcome::Finished(elapsed),
+                    answer: Some(answer),
+                },
+                Err(outcome) => PartOutcome {
+                    part,
+                    outcome,
+                    answer: None,
+                },
+            }
+        })
+        .collect();
+
+    PhasedRun { parse, parts }
+}
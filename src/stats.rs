@@ -0,0 +1,326 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path, time::Duration};
+
+use color_eyre::eyre::{Result, WrapErr, bail, eyre};
+use http_cache_surf::CacheMode;
+
+use crate::{DAYS, YEAR, fetch, puzzle::strip_tags};
+
+/// How long a part took to solve, counted from when the puzzle unlocked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveTime {
+    Within(Duration),
+    /// Shown as `>24h` by the site
+    OverADay,
+}
+
+/// Personal leaderboard entry for one part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartStats {
+    pub time: SolveTime,
+    pub rank: u32,
+    /// Only present in years with a global leaderboard
+    pub score: Option<u32>,
+}
+
+/// Personal leaderboard entries for one day, `None` for unsolved parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    pub day: u8,
+    pub parts: [Option<PartStats>; 2],
+}
+
+fn parse_time(s: &str) -> Result<SolveTime> {
+    if s == ">24h" {
+        return Ok(SolveTime::OverADay);
+    }
+
+    let seconds = s
+        .split(':')
+        .map(|n| n.parse::<u64>())
+        .try_fold(0, |total, n| n.map(|n| total * 60 + n))
+        .wrap_err_with(|| format!("Invalid time {s:?}"))?;
+
+    Ok(SolveTime::Within(Duration::from_secs(seconds)))
+}
+
+/// Parse one part's columns, `-` everywhere meaning unsolved
+fn parse_part(columns: &[&str]) -> Result<Option<PartStats>> {
+    if columns.iter().all(|&c| c == "-") {
+        return Ok(None);
+    }
+
+    Ok(Some(PartStats {
+        time: parse_time(columns[0])?,
+        rank: columns[1].parse()?,
+        score: columns.get(2).map(|s| s.parse()).transpose()?,
+    }))
+}
+
+/// Parse the table on the `/leaderboard/self` personal times page
+pub fn parse_personal_stats(html: &str) -> Result<Vec<DayStats>> {
+    let start = html
+        .find("<pre")
+        .ok_or_else(|| eyre!("No personal times table, is the session token set?"))?;
+    let end = html[start..]
+        .find("</pre>")
+        .ok_or_else(|| eyre!("Unterminated personal times table"))?;
+
+    let table = strip_tags(&html[start..start + end]);
+
+    // Data rows start with the day number, the header rows don't
+    table
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|columns| columns.first().is_some_and(|c| c.parse::<u8>().is_ok()))
+        .map(|columns| {
+            let (day, parts) = columns.split_first().unwrap();
+
+            // Time and rank, plus score in years with a global leaderboard
+            if parts.len() != 4 && parts.len() != 6 {
+                bail!("Unexpected personal times row {columns:?}");
+            }
+            let (part_1, part_2) = parts.split_at(parts.len() / 2);
+
+            Ok(DayStats {
+                day: day.parse()?,
+                parts: [parse_part(part_1)?, parse_part(part_2)?],
+            })
+        })
+        .collect()
+}
+
+/// Fetch personal times, falling back to the last cached copy when offline
+pub fn fetch_personal_stats() -> Result<Vec<DayStats>> {
+    let path = format!("/{YEAR}/leaderboard/self");
+
+    let html = fetch(&path, CacheMode::Reload).or_else(|e| {
+        eprintln!("Using cached personal times: {e:#}");
+        fetch(&path, CacheMode::OnlyIfCached)
+    })?;
+
+    parse_personal_stats(&html)
+}
+
+/// Answers recorded locally, stored as `day<TAB>part<TAB>answer` lines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, u8), String>);
+
+impl Answers {
+    /// Load answers from `path`, empty if it doesn't exist yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        match std::fs::read_to_string(path) {
+            Ok(text) => text.parse(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: impl ToString) {
+        self.0.insert((day, part), answer.to_string());
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut answers = Self::default();
+
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let mut columns = line.splitn(3, '\t');
+            let (Some(day), Some(part), Some(answer)) =
+                (columns.next(), columns.next(), columns.next())
+            else {
+                bail!("Expected day, part and answer separated by tabs, got {line:?}");
+            };
+
+            answers.record(day.parse()?, part.parse()?, answer);
+        }
+
+        Ok(answers)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for ((day, part), answer) in &self.0 {
+            writeln!(f, "{day}\t{part}\t{answer}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Progress on one part, combining the site's stats with the local answer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartProgress {
+    pub stats: Option<PartStats>,
+    pub answer: Option<String>,
+}
+
+impl PartProgress {
+    pub fn has_star(&self) -> bool {
+        self.stats.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayProgress {
+    pub day: u8,
+    pub parts: [PartProgress; 2],
+}
+
+/// Combine personal stats and local answers for every day of the event
+pub fn merge(stats: &[DayStats], answers: &Answers) -> Vec<DayProgress> {
+    (1..=DAYS)
+        .map(|day| {
+            let day_stats = stats.iter().find(|s| s.day == day);
+
+            let parts = [1, 2].map(|part| PartProgress {
+                stats: day_stats.and_then(|s| s.parts[part as usize - 1].clone()),
+                answer: answers.get(day, part).map(str::to_owned),
+            });
+
+            DayProgress { day, parts }
+        })
+        .collect()
+}
+
+fn format_time(time: SolveTime) -> String {
+    match time {
+        SolveTime::Within(duration) => {
+            let seconds = duration.as_secs();
+            format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )
+        }
+        SolveTime::OverADay => ">24h".to_owned(),
+    }
+}
+
+/// Render a calendar with stars, times, ranks and local answers per day
+pub fn render_calendar(progress: &[DayProgress]) -> String {
+    let mut out = String::new();
+
+    for day in progress {
+        let stars: String = day
+            .parts
+            .iter()
+            .map(|p| if p.has_star() { '*' } else { '.' })
+            .collect();
+
+        write!(out, "Day {:>2}  {stars}", day.day).unwrap();
+
+        for part in &day.parts {
+            let stats = match &part.stats {
+                Some(stats) => format!("{} #{}", format_time(stats.time), stats.rank),
+                None => "-".to_owned(),
+            };
+            let answer = part.answer.as_deref().unwrap_or("-");
+
+            write!(out, "  {stats:<16} {answer:<16}").unwrap();
+        }
+
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    }
+
+    let stars = progress
+        .iter()
+        .flat_map(|day| &day.parts)
+        .filter(|part| part.has_star())
+        .count();
+    writeln!(out, "\n{stars}/{} stars", progress.len() * 2).unwrap();
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERSONAL_TIMES: &str = include_str!("../fixtures/leaderboard_self.html");
+
+    #[test]
+    fn test_parse_personal_stats() {
+        let stats = parse_personal_stats(PERSONAL_TIMES).unwrap();
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats[2],
+            DayStats {
+                day: 1,
+                parts: [
+                    Some(PartStats {
+                        time: SolveTime::Within(Duration::from_secs(3 * 60 + 2)),
+                        rank: 397,
+                        score: None,
+                    }),
+                    Some(PartStats {
+                        time: SolveTime::OverADay,
+                        rank: 30712,
+                        score: None,
+                    }),
+                ],
+            }
+        );
+        assert_eq!(stats[0].day, 3);
+        assert_eq!(stats[0].parts[1], None);
+    }
+
+    #[test]
+    fn test_parse_with_score() {
+        let html = "<pre>      --------Part 1--------   --------Part 2--------
+Day       Time   Rank  Score       Time   Rank  Score
+  1   00:00:59     12     89   01:02:03   4567      0
+</pre>";
+        let stats = parse_personal_stats(html).unwrap();
+
+        assert_eq!(stats[0].parts[0].as_ref().unwrap().score, Some(89));
+        assert_eq!(
+            stats[0].parts[1].as_ref().unwrap().time,
+            SolveTime::Within(Duration::from_secs(3723))
+        );
+    }
+
+    #[test]
+    fn test_answers_round_trip() {
+        let mut answers = Answers::default();
+        answers.record(1, 1, 1147);
+        answers.record(1, 2, "6789");
+
+        let parsed: Answers = answers.to_string().parse().unwrap();
+        assert_eq!(parsed, answers);
+        assert_eq!(parsed.get(1, 2), Some("6789"));
+        assert_eq!(parsed.get(2, 1), None);
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let stats = parse_personal_stats(PERSONAL_TIMES).unwrap();
+        let mut answers = Answers::default();
+        answers.record(1, 1, 1147);
+
+        let progress = merge(&stats, &answers);
+        let calendar = render_calendar(&progress);
+
+        assert_eq!(progress.len(), DAYS as usize);
+        assert!(calendar.starts_with("Day  1  **  00:03:02 #397    1147"));
+        assert!(calendar.contains("Day  3  *.  "));
+        assert!(calendar.contains("Day 12  ..  -"));
+        assert!(calendar.ends_with("\n5/24 stars\n"));
+    }
+}
@@ -0,0 +1,243 @@
+mod mock;
+
+use std::{sync::OnceLock, time::Duration};
+
+use advent_of_code_2025::{
+    cache::{self, Selection},
+    fetch, fetch_input,
+    http::Client,
+    prefetch::{self, Status},
+    puzzle, stats,
+};
+use http_cache_surf::CacheMode;
+use mock::{LEADERBOARD_ID, MockServer, SESSION};
+
+const INPUT: &str = include_str!("../fixtures/mock/input_day01.txt");
+
+/// One server for the functions that configure themselves from the environment
+fn env_server() -> &'static MockServer {
+    static SERVER: OnceLock<MockServer> = OnceLock::new();
+
+    SERVER.get_or_init(|| {
+        let server = MockServer::start();
+
+        // SAFETY: every test touching the environment waits for this to finish first
+        unsafe {
+            std::env::set_var("AOC_BASE_URL", &server.base_url);
+            std::env::set_var("AOC_CACHE_DIR", server.cache_dir());
+            std::env::set_var("AOC_MIN_INTERVAL", "0");
+            std::env::set_var("AOC_USER_AGENT", "mock-tests");
+            std::env::set_var("SESSION", SESSION);
+        }
+
+        server
+    })
+}
+
+#[test]
+fn test_fetch_input_downloads_once() {
+    let server = env_server();
+
+    assert_eq!(fetch_input(1).unwrap(), INPUT);
+    assert_eq!(fetch_input(1).unwrap(), INPUT);
+    assert_eq!(server.hits("/2025/day/1/input"), 1);
+
+    // Other tests share the server, so only look at this test's requests
+    let requests = server.requests();
+    let request = requests
+        .iter()
+        .find(|request| request.path == "/2025/day/1/input")
+        .unwrap();
+    assert_eq!(request.user_agent.as_deref(), Some("mock-tests"));
+    assert_eq!(request.session.as_deref(), Some(SESSION));
+}
+
+#[test]
+fn test_show_puzzle() {
+    env_server();
+
+    let text = puzzle::show(1, false, false).unwrap();
+    assert!(text.contains("--- Day 1: Sample Puzzle ---"));
+    assert!(text.contains("--- Part Two ---"));
+
+    let error = puzzle::show(2, false, false).unwrap_err();
+    assert!(error.to_string().contains("404"));
+}
+
+#[test]
+fn test_fetch_personal_stats() {
+    env_server();
+
+    let stats = stats::fetch_personal_stats().unwrap();
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[2].day, 1);
+}
+
+#[test]
+fn test_private_leaderboard() {
+    env_server();
+
+    let path = format!("/2025/leaderboard/private/view/{LEADERBOARD_ID}.json");
+    let json: serde_json::Value =
+        serde_json::from_str(&fetch(&path, CacheMode::Reload).unwrap()).unwrap();
+
+    let member = &json["members"][LEADERBOARD_ID.to_string()];
+    assert_eq!(member["stars"], 3);
+    assert!(member["completion_day_level"]["1"]["2"]["get_star_ts"].is_u64());
+}
+
+#[test]
+fn test_session_is_checked() {
+    let server = MockServer::start();
+
+    let error = Client {
+        session: None,
+        ..server.client()
+    }
+    .input(1)
+    .unwrap_err();
+    assert!(error.to_string().contains("session token"));
+    assert!(server.requests().is_empty());
+
+    let error = Client {
+        session: Some("wrong".to_owned()),
+        ..server.client()
+    }
+    .input(1)
+    .unwrap_err();
+    assert!(error.to_string().contains("400"));
+    assert!(error.to_string().contains("log in"));
+}
+
+#[test]
+fn test_locked_day() {
+    let server = MockServer::start();
+    let client = server.client();
+
+    let error = client.input(12).unwrap_err();
+    assert!(error.to_string().contains("404"));
+
+    // Failures aren't cached, so the input can be fetched once it unlocks
+    assert!(!client.is_cached("/2025/day/12/input"));
+}
+
+#[test]
+fn test_server_errors() {
+    let server = MockServer::start();
+    let client = server.client();
+
+    server.fail_next(500, "Something broke");
+    let error = client.input(1).unwrap_err();
+    assert!(error.to_string().contains("500"));
+    assert!(error.to_string().contains("Something broke"));
+
+    assert_eq!(client.input(1).unwrap(), INPUT);
+}
+
+#[test]
+fn test_rate_limit() {
+    let server = MockServer::start();
+    server.rate_limit(Duration::from_millis(200));
+
+    let client = server.client();
+    client.get("/2025/day/1", CacheMode::Reload).unwrap();
+    let error = client.get("/2025/day/1", CacheMode::Reload).unwrap_err();
+    assert!(error.to_string().contains("429"));
+
+    // Cached responses don't touch the server at all
+    client.get("/2025/day/1", CacheMode::ForceCache).unwrap();
+    assert_eq!(server.hits("/2025/day/1"), 2);
+
+    let throttled = Client {
+        min_interval: Duration::from_millis(300),
+        ..client
+    };
+    throttled.get("/2025/day/1", CacheMode::Reload).unwrap();
+    throttled.get("/2025/day/1", CacheMode::Reload).unwrap();
+}
+
+#[test]
+fn test_submit() {
+    let server = MockServer::start();
+    let client = server.client();
+
+    let verdict = client.submit(1, 1, "3").unwrap();
+    assert!(verdict.starts_with("That's the right answer!"));
+
+    let verdict = client.submit(1, 2, "7").unwrap();
+    assert!(verdict.starts_with("That's not the right answer."));
+
+    let verdict = client.submit(1, 2, "6").unwrap();
+    assert!(verdict.starts_with("You gave an answer too recently"));
+
+    let submitted = server.requests().pop().unwrap();
+    assert_eq!(submitted.method, "POST");
+    assert_eq!(submitted.body, "level=2&answer=6");
+}
+
+#[test]
+fn test_purge_and_refetch() {
+    let server = MockServer::start();
+    let client = server.client();
+
+    client.input(1).unwrap();
+    client.get("/2025/day/1", CacheMode::ForceCache).unwrap();
+
+    let entries = cache::list(&client).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].url, client.url("/2025/day/1/input"));
+
+    let (status, body) = client.cached("/2025/day/1/input").unwrap();
+    assert!(status.is_success());
+    assert_eq!(body, INPUT);
+
+    let removed = cache::purge(&client, Selection::Day(1)).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert!(client.cached("/2025/day/1/input").is_err());
+
+    // No longer refused as a repeat download
+    assert_eq!(client.input(1).unwrap(), INPUT);
+    assert_eq!(server.hits("/2025/day/1/input"), 2);
+}
+
+#[test]
+fn test_prefetch() {
+    let server = MockServer::start();
+    let client = server.client();
+
+    let reports = prefetch::prefetch(&client, &[1, 2], true);
+    assert_eq!(reports[0].input, Status::Fetched);
+    assert_eq!(reports[0].puzzle, Some(Status::Fetched));
+    assert!(matches!(reports[1].input, Status::Failed(_)));
+    assert!(matches!(reports[1].puzzle, Some(Status::Failed(_))));
+
+    let reports = prefetch::prefetch(&client, &[1], false);
+    assert_eq!(reports[0].input, Status::Cached);
+    assert_eq!(reports[0].puzzle, None);
+    assert_eq!(server.hits("/2025/day/1/input"), 1);
+}
+
+#[test]
+fn test_prefetch_checks_session() {
+    let server = MockServer::start();
+    let client = Client {
+        session: None,
+        ..server.client()
+    };
+
+    let reports = prefetch::prefetch(&client, &[1], true);
+    assert!(
+        matches!(&reports[0].input, Status::Failed(reason) if reason.contains("session token"))
+    );
+    assert_eq!(reports[0].puzzle, Some(Status::Fetched));
+    assert_eq!(server.hits("/2025/day/1/input"), 0);
+
+    // An expired session gets turned away, and nothing is cached in place of the input
+    let client = Client {
+        session: Some("expired".to_owned()),
+        ..server.client()
+    };
+    let reports = prefetch::prefetch(&client, &[1], false);
+    assert!(matches!(reports[0].input, Status::Failed(_)));
+    assert!(!client.is_cached("/2025/day/1/input"));
+}
@@ -0,0 +1,306 @@
+//! In-process stand-in for adventofcode.com, serving fixtures over plain HTTP
+
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2025::http::Client;
+
+/// The only session cookie the server accepts
+pub const SESSION: &str = "mock-session";
+
+/// Private leaderboard the server has JSON for
+pub const LEADERBOARD_ID: u32 = 123456;
+
+/// Correct answers by day and part, anything else is wrong
+const ANSWERS: &[((u8, u8), &str)] = &[((1, 1), "3"), ((1, 2), "6")];
+
+/// How long a wrong answer locks out further submissions
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(60);
+
+const NOT_UNLOCKED: &str = "Please don't repeatedly request this endpoint before it unlocks! \
+The calendar countdown is synchronized with the server time; \
+the link will be enabled on the calendar the instant this puzzle becomes available.";
+
+const LOGGED_OUT: &str = "Puzzle inputs differ by user.  Please log in to get your puzzle input.";
+
+fn fixture(name: &str) -> Option<String> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(name);
+
+    std::fs::read_to_string(path).ok()
+}
+
+/// A request as the server saw it
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub user_agent: Option<String>,
+    pub session: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    requests: Vec<Request>,
+    /// Canned responses that take precedence over routing, oldest first
+    failures: VecDeque<(u16, String)>,
+    rate_limit: Option<Duration>,
+    last_request: Option<Instant>,
+    wrong_answer_at: Option<Instant>,
+}
+
+pub struct MockServer {
+    pub base_url: String,
+    cache_dir: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Listen on a free local port, serving requests on a background thread
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A client hanging up early only affects its own request
+                let _ = serve(stream, &shared);
+            }
+        });
+
+        let cache_dir = std::env::temp_dir().join(format!(
+            "aoc-mock-{}-{}",
+            std::process::id(),
+            address.port()
+        ));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+
+        Self {
+            base_url: format!("http://{address}"),
+            cache_dir,
+            state,
+        }
+    }
+
+    /// A client for this server with a fresh cache and no throttling
+    pub fn client(&self) -> Client {
+        Client {
+            base_url: self.base_url.clone(),
+            session: Some(SESSION.to_owned()),
+            user_agent: "mock-tests".to_owned(),
+            cache_dir: self.cache_dir.clone(),
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    pub fn cache_dir(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+
+    /// Every request so far, oldest first
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// How many requests were made for `path`
+    pub fn hits(&self, path: &str) -> usize {
+        self.requests().iter().filter(|r| r.path == path).count()
+    }
+
+    /// Answer the next request with `status` and `body`, whatever it asks for
+    pub fn fail_next(&self, status: u16, body: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.failures.push_back((status, body.to_owned()));
+    }
+
+    /// Answer 429 to requests that follow the previous one within `interval`
+    pub fn rate_limit(&self, interval: Duration) {
+        self.state.lock().unwrap().rate_limit = Some(interval);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.cache_dir);
+    }
+}
+
+fn serve(stream: TcpStream, state: &Mutex<State>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(path)) = (words.next(), words.next()) else {
+        return respond(&stream, 400, "Bad request line");
+    };
+
+    let mut request = Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        user_agent: None,
+        session: None,
+        body: String::new(),
+    };
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match name.to_ascii_lowercase().as_str() {
+            "user-agent" => request.user_agent = Some(value.to_owned()),
+            "cookie" => {
+                request.session = value
+                    .split(';')
+                    .find_map(|cookie| cookie.trim().strip_prefix("session="))
+                    .map(str::to_owned)
+            }
+            "content-length" => content_length = value.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    request.body = String::from_utf8_lossy(&body).into_owned();
+
+    let (status, body) = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+
+        let now = Instant::now();
+        let too_soon = state
+            .rate_limit
+            .zip(state.last_request)
+            .is_some_and(|(interval, last)| now - last < interval);
+        state.last_request = Some(now);
+
+        match state.failures.pop_front() {
+            Some(failure) => failure,
+            None if too_soon => (429, "Too many requests, slow down".to_owned()),
+            None => route(&request, &mut state),
+        }
+    };
+
+    respond(&stream, status, &body)
+}
+
+fn route(request: &Request, state: &mut State) -> (u16, String) {
+    let logged_in = request.session.as_deref() == Some(SESSION);
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+
+    let not_found = || (404, "404 Not Found".to_owned());
+    let ok = |body: Option<String>| body.map_or_else(not_found, |body| (200, body));
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["2025", "day", day]) => match *day {
+            "1" => ok(fixture("puzzle_both_parts.html")),
+            _ => (404, NOT_UNLOCKED.to_owned()),
+        },
+        ("GET", ["2025", "day", _, "input"]) if !logged_in => (400, LOGGED_OUT.to_owned()),
+        ("GET", ["2025", "day", day, "input"]) => {
+            match fixture(&format!("mock/input_day{day:0>2}.txt")) {
+                Some(input) => (200, input),
+                None => (404, NOT_UNLOCKED.to_owned()),
+            }
+        }
+        ("POST", ["2025", "day", _, "answer"]) if !logged_in => (400, LOGGED_OUT.to_owned()),
+        ("POST", ["2025", "day", day, "answer"]) => {
+            let form = |key: &str| {
+                request
+                    .body
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+                    .unwrap_or_default()
+            };
+            let expected = ANSWERS
+                .iter()
+                .find(|((d, part), _)| d.to_string() == *day && part.to_string() == form("level"));
+
+            if state
+                .wrong_answer_at
+                .is_some_and(|at| at.elapsed() < ANSWER_TIMEOUT)
+            {
+                return ok(fixture("mock/answer_too_recent.html"));
+            }
+
+            match expected {
+                Some((_, answer)) if *answer == form("answer") => {
+                    ok(fixture("mock/answer_right.html"))
+                }
+                _ => {
+                    state.wrong_answer_at = Some(Instant::now());
+                    ok(fixture("mock/answer_wrong.html"))
+                }
+            }
+        }
+        // The real site shows a login page rather than an error
+        ("GET", ["2025", "leaderboard", "self"]) if !logged_in => (
+            200,
+            "<main><p>Log in to see your times.</p></main>".to_owned(),
+        ),
+        ("GET", ["2025", "leaderboard", "self"]) => ok(fixture("leaderboard_self.html")),
+        ("GET", ["2025", "leaderboard", "private", "view", file]) => {
+            if !logged_in {
+                return (302, String::new());
+            }
+
+            match file.strip_suffix(".json") {
+                Some(id) if id == LEADERBOARD_ID.to_string() => {
+                    ok(fixture("mock/private_leaderboard.json"))
+                }
+                _ => not_found(),
+            }
+        }
+        _ => not_found(),
+    }
+}
+
+fn respond(mut stream: &TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        302 => "Found",
+        400 => "Bad Request",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    };
+    let location = match status {
+        302 => "Location: /2025/auth/login\r\n",
+        _ => "",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\n{location}Content-Type: text/html; charset=utf-8\r\n\
+Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}